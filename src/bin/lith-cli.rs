@@ -0,0 +1,63 @@
+//! Convert an image to a flat lithophane STL without opening a window
+
+use clap::Parser;
+use lith::gen::{
+    flat_mesh::FlatMeshGenerator, standard_image::StandardImagePreprocessor, ImagePreprocessor,
+    LithophaneGenerator,
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+#[derive(Parser)]
+#[command(about = "Convert an image to a flat lithophane STL")]
+struct Args {
+    /// Image to convert
+    input: PathBuf,
+    /// Where to write the STL. Paths ending in .gz are gzip-compressed.
+    output: PathBuf,
+    /// Number of samples across the image
+    #[arg(short, long, default_value_t = 80)]
+    width: usize,
+    /// Relief depth over a black pixel, in mm
+    #[arg(short, long, default_value_t = 2.0)]
+    scaling: f32,
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("error: {msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let image = lith::img::load_image(&args.input)
+        .map_err(|e| format!("couldn't load {}: {e}", args.input.display()))?;
+    let map = StandardImagePreprocessor::default()
+        .width(args.width)
+        .transform(&image);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(args.scaling)
+        .generate(map)
+        .map_err(|e| format!("couldn't generate the lithophane: {e}"))?;
+
+    let compress = args.output.extension().is_some_and(|e| e == "gz");
+    File::create(&args.output)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            if compress {
+                mesh.write_stl_gz(&mut writer)?;
+            } else {
+                mesh.write_stl(&mut writer)?;
+            }
+            writer.flush()
+        })
+        .map_err(|e| format!("couldn't write {}: {e}", args.output.display()))
+}
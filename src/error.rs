@@ -0,0 +1,85 @@
+use crate::gen::GenError;
+
+/// Errors produced by the high-level lithophane pipeline
+#[derive(Debug)]
+pub enum LithError {
+    /// The requested physical height doesn't match the aspect ratio of the image
+    AspectMismatch {
+        /// The height in millimeters that would keep the image's aspect ratio
+        expected_mm: f32,
+        /// The height in millimeters that was requested
+        requested_mm: f32,
+    },
+    /// The options can't produce a mesh, with a description of why
+    InvalidOptions(&'static str),
+    /// The image file couldn't be read
+    Io(std::io::Error),
+    /// The file isn't an image, or is in a format that can't be decoded
+    UnsupportedFormat,
+    /// The file looks like an image but couldn't be decoded, with the decoder's description
+    CorruptImage(String),
+    /// The image decoded successfully but has no pixels
+    EmptyImage,
+    /// The generator couldn't build a mesh from the light map
+    Generate(GenError),
+}
+
+impl std::fmt::Display for LithError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LithError::AspectMismatch {
+                expected_mm,
+                requested_mm,
+            } => write!(
+                f,
+                "a height of {requested_mm:.1}mm doesn't match the image's aspect ratio, \
+                 expected about {expected_mm:.1}mm"
+            ),
+            LithError::InvalidOptions(reason) => write!(f, "invalid options: {reason}"),
+            LithError::Io(err) => write!(f, "couldn't read the file: {err}"),
+            LithError::UnsupportedFormat => {
+                write!(f, "the file isn't an image in a supported format")
+            }
+            LithError::CorruptImage(reason) => {
+                write!(f, "the image is damaged or incomplete: {reason}")
+            }
+            LithError::EmptyImage => write!(f, "the image has no pixels"),
+            LithError::Generate(err) => write!(f, "couldn't generate the mesh: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LithError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LithError::Io(err) => Some(err),
+            LithError::Generate(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for LithError {
+    fn from(err: std::io::Error) -> Self {
+        LithError::Io(err)
+    }
+}
+
+impl From<GenError> for LithError {
+    fn from(err: GenError) -> Self {
+        LithError::Generate(err)
+    }
+}
+
+impl From<image::ImageError> for LithError {
+    fn from(err: image::ImageError) -> Self {
+        match err {
+            image::ImageError::Unsupported(_) => LithError::UnsupportedFormat,
+            image::ImageError::IoError(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                LithError::CorruptImage(err.to_string())
+            }
+            image::ImageError::IoError(err) => LithError::Io(err),
+            err => LithError::CorruptImage(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::img::{LightnessConversion, LuminanceWeights};
+
+use super::{fitted_dims, resize_bounds, ImagePreprocessor, LightMap};
+
+/// Image preprocessor that blurs the resized image, so sensor noise and grain don't print as
+/// bumps in the relief
+pub struct BlurImagePreprocessor {
+    width: usize,
+    sigma: f32,
+    conversion: LightnessConversion,
+}
+
+impl BlurImagePreprocessor {
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Get the (width, height) of the light map produced for an image of the given size
+    pub fn output_dims(&self, width: u32, height: u32) -> (usize, usize) {
+        fitted_dims(width, height, (self.width, 0))
+    }
+
+    /// Set the standard deviation of the Gaussian blur, in pixels of the resized image. A
+    /// sigma of 0.0, the default, leaves the image sharp.
+    pub fn sigma(mut self, sigma: f32) -> Self {
+        self.sigma = sigma;
+        self
+    }
+
+    /// Set how far in pixels the blur reaches. The kernel is cut off at two standard
+    /// deviations, so this sets a sigma of half the radius. Pixels near the edge of the image
+    /// are blurred with only the weights that fall inside it.
+    pub fn radius(self, radius: f32) -> Self {
+        self.sigma(radius / 2.0)
+    }
+
+    /// Set the gamma applied to each pixel in linear light, before the luminance is computed.
+    /// See [`LightnessConversion`] for the full order of operations.
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.conversion = self.conversion.linear_gamma(gamma);
+        self
+    }
+
+    /// Multiply the relief of each pixel by its opacity, so transparent areas are carved
+    /// thinner. See [`LightnessConversion`] for the exact formula.
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.alpha_relief(enabled);
+        self
+    }
+
+    /// Map luminance to perceived lightness before it becomes relief, which is on by default.
+    /// See [`LightnessConversion::perceptual`].
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.perceptual(enabled);
+        self
+    }
+
+    /// Swap light and dark so bright areas get the thick relief, for front-lit pieces. See
+    /// [`LightnessConversion::invert`].
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.invert(enabled);
+        self
+    }
+
+    /// Drop pixels more transparent than `threshold` to the base. See
+    /// [`LightnessConversion::alpha_threshold`].
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.conversion = self.conversion.alpha_threshold(threshold);
+        self
+    }
+
+    /// Set the lightness of pixels below the alpha threshold. See
+    /// [`LightnessConversion::transparent_lightness`].
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.conversion = self.conversion.transparent_lightness(lightness);
+        self
+    }
+
+    /// Set the weights used to sum each pixel's channels into luminance. See
+    /// [`LuminanceWeights`].
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.conversion = self.conversion.weights(weights);
+        self
+    }
+}
+
+impl Default for BlurImagePreprocessor {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            sigma: 0.0,
+            conversion: LightnessConversion::default(),
+        }
+    }
+}
+
+impl ImagePreprocessor for BlurImagePreprocessor {
+    fn transform(self, image: &image::DynamicImage) -> LightMap {
+        let (width, height) = resize_bounds(image.width(), image.height(), (self.width, 0));
+        let image = image.resize(width, height, image::imageops::FilterType::CatmullRom);
+        // Blurring after the resize keeps the kernel small
+        let image = if self.sigma > 0.0 {
+            image.blur(self.sigma)
+        } else {
+            image
+        };
+        LightMap {
+            lightnesses: self.conversion.lightnesses(&image),
+            dims: (image.width() as usize, image.height() as usize),
+        }
+    }
+}
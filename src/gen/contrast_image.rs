@@ -0,0 +1,127 @@
+use crate::img::LuminanceWeights;
+
+use super::{fitted_dims, standard_image::StandardImagePreprocessor, ImagePreprocessor, LightMap};
+
+/// Image preprocessor that adjusts the contrast and gamma of the light map, to liven up flat
+/// photos before they become relief
+pub struct ContrastImagePreprocessor {
+    width: usize,
+    gamma: f32,
+    contrast: f32,
+    /// Converts the pixels to lightness before the adjustments
+    standard: StandardImagePreprocessor,
+}
+
+impl ContrastImagePreprocessor {
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Get the (width, height) of the light map produced for an image of the given size
+    pub fn output_dims(&self, width: u32, height: u32) -> (usize, usize) {
+        fitted_dims(width, height, (self.width, 0))
+    }
+
+    /// Set the gamma applied to each pixel in linear light, before the luminance is computed.
+    /// See [`StandardImagePreprocessor::linear_gamma`].
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.standard = self.standard.linear_gamma(gamma);
+        self
+    }
+
+    /// Multiply the relief of each pixel by its opacity, so transparent areas are carved
+    /// thinner. See [`StandardImagePreprocessor::alpha_relief`].
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.standard = self.standard.alpha_relief(enabled);
+        self
+    }
+
+    /// Map luminance to perceived lightness before it becomes relief, which is on by default.
+    /// See [`StandardImagePreprocessor::perceptual`].
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.standard = self.standard.perceptual(enabled);
+        self
+    }
+
+    /// Swap light and dark so bright areas get the thick relief, for front-lit pieces. The
+    /// lightness is inverted before the contrast and gamma are applied. See
+    /// [`StandardImagePreprocessor::invert`].
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.standard = self.standard.invert(enabled);
+        self
+    }
+
+    /// Drop pixels more transparent than `threshold` to the base. See
+    /// [`StandardImagePreprocessor::alpha_threshold`].
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.standard = self.standard.alpha_threshold(threshold);
+        self
+    }
+
+    /// Set the lightness of pixels below the alpha threshold. See
+    /// [`StandardImagePreprocessor::transparent_lightness`].
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.standard = self.standard.transparent_lightness(lightness);
+        self
+    }
+
+    /// Set the weights used to sum each pixel's channels into luminance. See
+    /// [`LuminanceWeights`].
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.standard = self.standard.weights(weights);
+        self
+    }
+
+    /// Raise each lightness to `gamma` after the contrast is applied. Values above 1.0 darken
+    /// the midtones and values below 1.0 lighten them.
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Stretch each lightness away from mid gray by `contrast`, clamping to the 0.0..=1.0
+    /// range. Values above 1.0 add contrast and values below 1.0 take it away.
+    pub fn contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast;
+        self
+    }
+
+    /// Apply the contrast and then the gamma to a single lightness. Neutral settings are
+    /// skipped so they leave the lightness exactly as it was.
+    fn adjust(&self, l: f32) -> f32 {
+        let l = if self.contrast == 1.0 {
+            l
+        } else {
+            ((l - 0.5) * self.contrast + 0.5).clamp(0.0, 1.0)
+        };
+        if self.gamma == 1.0 {
+            l
+        } else {
+            l.powf(self.gamma)
+        }
+    }
+}
+
+impl Default for ContrastImagePreprocessor {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            gamma: 1.0,
+            contrast: 1.0,
+            standard: StandardImagePreprocessor::default(),
+        }
+    }
+}
+
+impl ImagePreprocessor for ContrastImagePreprocessor {
+    fn transform(mut self, image: &image::DynamicImage) -> LightMap {
+        let mut map = std::mem::take(&mut self.standard)
+            .width(self.width)
+            .transform(image);
+        for l in &mut map.lightnesses {
+            *l = self.adjust(*l);
+        }
+        map
+    }
+}
@@ -1,6 +1,12 @@
+use std::f32::consts::PI;
+
 use crate::geo::{Mesh, Vec3};
 
-use super::{LightMap, LithophaneGenerator};
+use super::{morton_order, GenError, LightMap, LithophaneGenerator, ReliefProfile, TriangleOrder};
+
+/// Largest relief depth allowed, as a fraction of the radius. Deeper relief would push the
+/// interior wall through the axis of the cylinder and turn the mesh inside out.
+const MAX_DEPTH_FRACTION: f32 = 0.9;
 
 pub struct CylinderMeshGenerator {
     scaling: f32,
@@ -11,49 +17,233 @@ pub struct CylinderMeshGenerator {
     tris: Vec<Vec3>,
     bottom: f32,
     radius: f32,
+    order: TriangleOrder,
+    tiles: usize,
+    seam_blend: usize,
+    keep_aspect: bool,
+    profile: ReliefProfile,
+    exterior: Vec<Vec3>,
+    interior: Vec<Vec3>,
+    base_thickness: f32,
+    invert: bool,
+    arc: Option<(f32, f32)>,
 }
 
 impl CylinderMeshGenerator {
-    /// Set the scale multiplier for the generator to use.
+    /// Set the relief depth, from the inside wall to the outside over a pure black pixel. See
+    /// [`LithophaneGenerator`] for how this is shared between generators. The relief is limited
+    /// to 90% of the radius, so larger values are clamped.
     pub fn scaling(mut self, scaling: f32) -> Self {
         // Negative scaling makes the lithophane work normally
         self.scaling = -scaling;
         self
     }
-    
+
     /// Set the target height of the cylinder
     pub fn height(mut self, height: f32) -> Self {
         // the field `height` is used for the height of the source image
         self.size = height;
         self
     }
-    
+
+    /// Put a solid backing `t` thick inside the relief, so even pure white pixels are that
+    /// thick. The relief depth is unchanged and the inside wall moves inward instead. The base
+    /// and relief together are still limited to 90% of the radius.
+    pub fn base_thickness(mut self, t: f32) -> Self {
+        self.base_thickness = t;
+        self
+    }
+
+    /// Raise the light areas instead of the dark ones, for a positive relief that reads under
+    /// front lighting, such as signage. The base and walls are unchanged.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
     /// Set the target radius of the cylinder
     pub fn radius(mut self, radius: f32) -> Self {
         self.radius = radius;
         self
     }
 
+    /// Wrap the image around only part of the cylinder, from `start_deg` to `end_deg`
+    /// counterclockwise, for a curved panel instead of a closed tube. The open ends are closed
+    /// with flat side walls. An arc of 360 degrees or more is a full cylinder again.
+    pub fn arc(mut self, start_deg: f32, end_deg: f32) -> Self {
+        let (start, end) = (start_deg.min(end_deg), start_deg.max(end_deg));
+        self.arc = if end - start >= 360.0 {
+            None
+        } else {
+            Some((start.to_radians(), end.to_radians()))
+        };
+        self
+    }
+
+    /// Get the (base, surface) distances of the relief from the axis of the cylinder: the
+    /// inside wall and the outside over a pure black pixel. When inverted, black sits at the
+    /// bottom of the relief instead of at the full radius.
+    pub fn relief_range(&self) -> (f32, f32) {
+        let surface = if self.invert {
+            self.radius + self.clamped_scaling()
+        } else {
+            self.radius
+        };
+        (self.radius + self.clamped_bottom(), surface)
+    }
+
+    /// Get the scaling actually used, with the relief depth limited to `MAX_DEPTH_FRACTION` of
+    /// the radius so the interior wall always keeps a positive radius
+    fn clamped_scaling(&self) -> f32 {
+        self.scaling.max(-self.radius * MAX_DEPTH_FRACTION)
+    }
+
+    /// Get the offset of the inside wall from the radius, below the relief by the base
+    /// thickness and limited the same way as the scaling
+    fn clamped_bottom(&self) -> f32 {
+        (self.clamped_scaling() - self.base_thickness).max(-self.radius * MAX_DEPTH_FRACTION)
+    }
+
+    /// Set the order the triangles are emitted in. `Grouped` emits all exterior faces, then all
+    /// interior faces, then the bridges between them.
+    pub fn order(mut self, order: TriangleOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Repeat the source image `tiles` times around the circumference
+    pub fn tiles(mut self, tiles: usize) -> Self {
+        self.tiles = tiles.max(1);
+        self
+    }
+
+    /// Fade the last `columns` columns of each tile into its first column, so the tiles (and
+    /// the wrap-around seam) join without a visible step.
+    pub fn seam_blend(mut self, columns: usize) -> Self {
+        self.seam_blend = columns;
+        self
+    }
+
+    /// Keep the image's aspect ratio instead of stretching it to cover the whole cylinder. The
+    /// band is centered and the rest of the wall is filled with a flat margin at full thickness.
+    pub fn keep_aspect(mut self, keep: bool) -> Self {
+        self.keep_aspect = keep;
+        self
+    }
+
+    /// Set how lightness is turned into relief depth. `Stepped` gives logos crisp flat levels.
+    pub fn relief_profile(mut self, profile: ReliefProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Estimate how many triangles the mesh for a light map of size `dims` will have
+    pub fn estimated_triangles(&self, dims: (usize, usize)) -> usize {
+        let (width, height) = (dims.0 * self.tiles, dims.1);
+        let (width, height) = if self.keep_aspect {
+            let (left, top, right, bottom) = self.aspect_padding((width, height));
+            (left + width + right, top + height + bottom)
+        } else {
+            (width, height)
+        };
+        // Two walls of quads, their seams and the rims joining them. An open arc trades the
+        // seams for its two end walls, which are one quad shorter.
+        if self.arc.is_some() {
+            (4 * width * height).saturating_sub(4)
+        } else {
+            4 * width * height
+        }
+    }
+
+    /// Get the (left, top, right, bottom) padding that makes the pixels of a light map of size
+    /// `dims` come out square once wrapped around the cylinder
+    fn aspect_padding(&self, dims: (usize, usize)) -> (usize, usize, usize, usize) {
+        let (width, height) = dims;
+        let span = self.arc.map_or(2.0 * PI, |(start, end)| end - start);
+        let circumference = span * self.radius;
+        if circumference <= 0.0 || self.size <= 0.0 {
+            return (0, 0, 0, 0);
+        }
+
+        // Rows needed for the full height at the current column pitch, or failing that the
+        // columns needed for the full circumference at the current row pitch
+        let rows = (width as f32 * self.size / circumference).round() as usize;
+        if rows >= height {
+            let extra = rows - height;
+            (0, extra / 2, 0, extra - extra / 2)
+        } else {
+            let columns = (height as f32 * circumference / self.size).round() as usize;
+            let extra = columns.saturating_sub(width);
+            (extra / 2, 0, extra - extra / 2, 0)
+        }
+    }
+
+    /// Pad `source` so that its pixels come out square once wrapped around the cylinder
+    fn pad_to_aspect(&self, source: LightMap) -> LightMap {
+        let pad = self.aspect_padding(source.dims());
+        // A lightness of 0 gives the full relief thickness
+        source.pad(pad, 0.0)
+    }
+
     /// Generate a heightmap for the current source and save it to `self.heights`
     fn generate_heightmap(&mut self, source: LightMap) {
         self.heights.reserve(source.dims.0 * source.dims.1);
 
         // Calculate the percieved lightness of each pixel and scale to get the final heightmap
+        let scaling = self.clamped_scaling();
         source
             .lightnesses
             .iter()
-            .map(|l| l * self.scaling)
+            .map(|l| {
+                // Inverting raises the light areas instead of the dark ones
+                let l = if self.invert { 1.0 - l } else { *l };
+                l * scaling
+            })
             .for_each(|h| {
                 self.heights.push(h);
             });
 
-        self.bottom = 1.0 * self.scaling;
+        // The backing goes inside the relief, leaving the outside where it was
+        self.bottom = self.clamped_bottom();
+    }
+
+    /// Compute every exterior and interior vertex once, so the walls and the bridges between
+    /// them share bit-identical corners
+    fn build_vertices(&mut self) {
+        let points = (0..self.height).flat_map(|y| (0..self.width).map(move |x| (x, y)));
+        self.exterior = points
+            .clone()
+            .map(|(x, y)| self.compute_vertex(x, y))
+            .collect();
+        self.interior = points
+            .map(|(x, y)| self.compute_interior_vertex(x, y))
+            .collect();
     }
 
     /// Get the vertex at (x, y, heights[x, y])
     fn get_vertex(&self, x: usize, y: usize) -> Vec3 {
-        let angle = (x as f32 / self.width as f32) * 2.0 * std::f32::consts::PI;
-        let (sin, cos) = angle.sin_cos();
+        self.exterior[y * self.width + x].clone()
+    }
+
+    /// Get the vertex on the interior wall at (x, y)
+    fn get_interior_vertex(&self, x: usize, y: usize) -> Vec3 {
+        self.interior[y * self.width + x].clone()
+    }
+
+    /// Get the angle of column x around the axis
+    fn column_angle(&self, x: usize) -> f32 {
+        match self.arc {
+            // The last column stops a step short of the first, leaving room for the seam
+            None => (x as f32 / self.width as f32) * 2.0 * PI,
+            // There's no seam on an open arc, so the columns run all the way to the end
+            Some((start, end)) => {
+                start + (x as f32 / (self.width - 1).max(1) as f32) * (end - start)
+            }
+        }
+    }
+
+    fn compute_vertex(&self, x: usize, y: usize) -> Vec3 {
+        let (sin, cos) = self.column_angle(x).sin_cos();
         let height = self.heights[y * self.width + x];
         let radius = self.radius + height;
         Vec3 {
@@ -63,9 +253,8 @@ impl CylinderMeshGenerator {
         }
     }
 
-    fn get_interior_vertex(&self, x: usize, y: usize) -> Vec3 {
-        let angle = (x as f32 / self.width as f32) * 2.0 * std::f32::consts::PI;
-        let (sin, cos) = angle.sin_cos();
+    fn compute_interior_vertex(&self, x: usize, y: usize) -> Vec3 {
+        let (sin, cos) = self.column_angle(x).sin_cos();
         let radius = self.radius + self.bottom;
         Vec3 {
             x: radius * cos,
@@ -74,7 +263,6 @@ impl CylinderMeshGenerator {
         }
     }
 
-
     /// Add a quad whose bottom-right vertex is at (x, y)
     fn add_quad(&mut self, x: usize, y: usize) {
         let tl = self.get_vertex(x - 1, y - 1);
@@ -95,18 +283,28 @@ impl CylinderMeshGenerator {
     }
 
     fn bridge_edge_loop(&mut self, y: usize) {
-            let tl = self.get_vertex(self.width - 1, y - 1);
-            let bl = self.get_vertex(self.width - 1, y);
-            let tr = self.get_vertex(0, y - 1);
-            let br = self.get_vertex(0, y);
-            self.tris
-                .extend_from_slice(&[tl.clone(), bl, br.clone(), tl, br, tr]);
-            let tl = self.get_interior_vertex(self.width -1, y - 1);
-            let bl = self.get_interior_vertex(self.width - 1, y);
-            let tr = self.get_interior_vertex(0, y - 1);
-            let br = self.get_interior_vertex(0, y);
-            self.tris
-                .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
+        self.bridge_exterior_seam(y);
+        self.bridge_interior_seam(y);
+    }
+
+    /// Close the exterior wall between the last and first column of row y
+    fn bridge_exterior_seam(&mut self, y: usize) {
+        let tl = self.get_vertex(self.width - 1, y - 1);
+        let bl = self.get_vertex(self.width - 1, y);
+        let tr = self.get_vertex(0, y - 1);
+        let br = self.get_vertex(0, y);
+        self.tris
+            .extend_from_slice(&[tl.clone(), bl, br.clone(), tl, br, tr]);
+    }
+
+    /// Close the interior wall between the last and first column of row y
+    fn bridge_interior_seam(&mut self, y: usize) {
+        let tl = self.get_interior_vertex(self.width - 1, y - 1);
+        let bl = self.get_interior_vertex(self.width - 1, y);
+        let tr = self.get_interior_vertex(0, y - 1);
+        let br = self.get_interior_vertex(0, y);
+        self.tris
+            .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
     }
 
     fn bridge_int_ext(&mut self, x: usize) {
@@ -116,7 +314,7 @@ impl CylinderMeshGenerator {
         let br = self.get_vertex(x, self.height - 1);
         self.tris
             .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
-        
+
         let tl = self.get_vertex(x - 1, 0);
         let bl = self.get_interior_vertex(x - 1, 0);
         let tr = self.get_vertex(x, 0);
@@ -132,7 +330,7 @@ impl CylinderMeshGenerator {
         let br = self.get_vertex(0, self.height - 1);
         self.tris
             .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
-        
+
         let tl = self.get_vertex(self.width - 1, 0);
         let bl = self.get_interior_vertex(self.width - 1, 0);
         let tr = self.get_vertex(0, 0);
@@ -140,6 +338,26 @@ impl CylinderMeshGenerator {
         self.tris
             .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
     }
+
+    /// Close both ends of an open arc with a side wall between the exterior and interior
+    fn close_arc_ends(&mut self) {
+        let last = self.width - 1;
+        for y in 1..self.height {
+            let et = self.get_vertex(0, y - 1);
+            let eb = self.get_vertex(0, y);
+            let it = self.get_interior_vertex(0, y - 1);
+            let ib = self.get_interior_vertex(0, y);
+            self.tris
+                .extend_from_slice(&[et.clone(), it, ib.clone(), et, ib, eb]);
+
+            let et = self.get_vertex(last, y - 1);
+            let eb = self.get_vertex(last, y);
+            let it = self.get_interior_vertex(last, y - 1);
+            let ib = self.get_interior_vertex(last, y);
+            self.tris
+                .extend_from_slice(&[et.clone(), ib.clone(), it, et, eb, ib]);
+        }
+    }
 }
 
 impl Default for CylinderMeshGenerator {
@@ -153,32 +371,113 @@ impl Default for CylinderMeshGenerator {
             heights: vec![],
             tris: vec![],
             bottom: f32::MAX,
+            order: TriangleOrder::default(),
+            tiles: 1,
+            seam_blend: 0,
+            keep_aspect: false,
+            profile: ReliefProfile::default(),
+            exterior: vec![],
+            interior: vec![],
+            base_thickness: 0.0,
+            invert: false,
+            arc: None,
         }
     }
 }
 
 impl LithophaneGenerator for CylinderMeshGenerator {
-    fn generate(mut self, source: LightMap) -> crate::geo::Mesh {
+    fn generate_with_progress(
+        mut self,
+        mut source: LightMap,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<Mesh, GenError> {
+        source.validate()?;
+        if self.radius <= 0.0 {
+            return Err(GenError::EmptyDimensions("radius"));
+        }
+        if self.size <= 0.0 {
+            return Err(GenError::EmptyDimensions("height"));
+        }
+
+        source.apply_profile(self.profile);
+        if self.seam_blend > 0 {
+            source.blend_seam(self.seam_blend);
+        }
+        if self.tiles > 1 {
+            source = source.tile_horizontal(self.tiles);
+        }
+        if self.keep_aspect {
+            source = self.pad_to_aspect(source);
+        }
+
         let (width, height) = source.dims;
         self.generate_heightmap(source);
         self.width = width;
         self.height = height;
+        self.build_vertices();
+        // An open arc has no seam to bridge, just two ends to close
+        let closed = self.arc.is_none();
+
+        match self.order {
+            TriangleOrder::RowMajor => {
+                for y in 1..height {
+                    for x in 1..width {
+                        self.add_quad(x, y);
+                        self.add_interior_quad(x, y);
+                    }
 
-        for y in 1..height {
-            for x in 1..width {
-                self.add_quad(x, y);
-                self.add_interior_quad(x, y);
+                    if closed {
+                        self.bridge_edge_loop(y);
+                    }
+                    progress(y as f32 / height as f32);
+                }
+            }
+            TriangleOrder::Grouped => {
+                for y in 1..height {
+                    for x in 1..width {
+                        self.add_quad(x, y);
+                    }
+                    if closed {
+                        self.bridge_exterior_seam(y);
+                    }
+                    progress(y as f32 / height as f32 / 2.0);
+                }
+                for y in 1..height {
+                    for x in 1..width {
+                        self.add_interior_quad(x, y);
+                    }
+                    if closed {
+                        self.bridge_interior_seam(y);
+                    }
+                    progress((height + y) as f32 / height as f32 / 2.0);
+                }
+            }
+            TriangleOrder::Morton => {
+                let quads = morton_order(width, height);
+                quads.iter().for_each(|&(x, y)| self.add_quad(x, y));
+                if closed {
+                    (1..height).for_each(|y| self.bridge_exterior_seam(y));
+                }
+                quads
+                    .iter()
+                    .for_each(|&(x, y)| self.add_interior_quad(x, y));
+                if closed {
+                    (1..height).for_each(|y| self.bridge_interior_seam(y));
+                }
             }
-    
-            self.bridge_edge_loop(y);
         }
 
         for x in 1..width {
             self.bridge_int_ext(x);
         }
 
-        self.bridge_int_ext_loop();
+        if closed {
+            self.bridge_int_ext_loop();
+        } else {
+            self.close_arc_ends();
+        }
+        progress(1.0);
 
-        Mesh::new(self.tris)
+        Ok(Mesh::new(self.tris))
     }
 }
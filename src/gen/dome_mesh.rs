@@ -0,0 +1,200 @@
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use crate::geo::{Mesh, Vec3};
+
+use super::{GenError, LightMap, LithophaneGenerator};
+
+/// Largest relief depth allowed, as a fraction of the radius, for the same reason as the
+/// cylinder: deeper relief would push the interior wall through the center.
+const MAX_DEPTH_FRACTION: f32 = 0.9;
+
+/// Smallest and largest half-angle of the opening at the top, in degrees. Without an opening
+/// the first row would collapse to a point at the pole.
+const OPENING_RANGE: (f32, f32) = (1.0, 80.0);
+
+/// Hemispherical lithophane generator for lampshade-style pieces. Rows run from the rim of an
+/// opening at the top down to the equator, which sits flat on the bed, and columns run around
+/// the vertical axis.
+pub struct DomeMeshGenerator {
+    scaling: f32,
+    radius: f32,
+    opening: f32,
+    width: usize,
+    height: usize,
+    heights: Vec<f32>,
+    tris: Vec<Vec3>,
+    exterior: Vec<Vec3>,
+    interior: Vec<Vec3>,
+}
+
+impl DomeMeshGenerator {
+    /// Set the relief depth, from the inside wall to the outside over a pure black pixel. See
+    /// [`LithophaneGenerator`] for how this is shared between generators. The relief is limited
+    /// to 90% of the radius, so larger values are clamped.
+    pub fn scaling(mut self, scaling: f32) -> Self {
+        // Negative scaling makes the lithophane work normally
+        self.scaling = -scaling;
+        self
+    }
+
+    /// Set the outside radius of the dome
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Set the half-angle of the opening at the top, measured from the vertical axis, e.g. to
+    /// fit a lamp holder. Clamped to between 1 and 80 degrees.
+    pub fn opening(mut self, degrees: f32) -> Self {
+        self.opening = degrees.clamp(OPENING_RANGE.0, OPENING_RANGE.1).to_radians();
+        self
+    }
+
+    /// Get the (base, surface) distances of the relief from the center of the dome: the inside
+    /// wall and the outside over a pure black pixel
+    pub fn relief_range(&self) -> (f32, f32) {
+        (self.radius + self.clamped_scaling(), self.radius)
+    }
+
+    /// Estimate how many triangles the mesh for a light map of size `dims` will have
+    pub fn estimated_triangles(&self, dims: (usize, usize)) -> usize {
+        // Two walls of quads, their seams and the rings at the opening and the equator
+        4 * dims.0 * dims.1
+    }
+
+    /// Get the scaling actually used, with the relief depth limited to `MAX_DEPTH_FRACTION` of
+    /// the radius so the interior wall always keeps a positive radius
+    fn clamped_scaling(&self) -> f32 {
+        self.scaling.max(-self.radius * MAX_DEPTH_FRACTION)
+    }
+
+    /// Get the unit direction from the center to sample (x, y). Row 0 is at the rim of the
+    /// opening and the last row is on the equator.
+    fn direction(&self, x: usize, y: usize) -> Vec3 {
+        let rows = (self.height - 1).max(1) as f32;
+        let polar = self.opening + (y as f32 / rows) * (FRAC_PI_2 - self.opening);
+        // The last column stops a step short of the first, leaving room for the seam
+        let azimuth = (x as f32 / self.width as f32) * 2.0 * PI;
+        let (sin_polar, cos_polar) = polar.sin_cos();
+        let (sin_azimuth, cos_azimuth) = azimuth.sin_cos();
+        Vec3 {
+            x: sin_polar * cos_azimuth,
+            y: sin_polar * sin_azimuth,
+            z: cos_polar,
+        }
+    }
+
+    /// Compute every exterior and interior vertex once, so the walls and the rings between
+    /// them share bit-identical corners
+    fn build_vertices(&mut self) {
+        let bottom = self.radius + self.clamped_scaling();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let d = self.direction(x, y);
+                let outside = self.radius + self.heights[y * self.width + x];
+                self.exterior.push(&d * outside);
+                self.interior.push(&d * bottom);
+            }
+        }
+    }
+
+    /// Get the vertex on the exterior wall at (x, y)
+    fn get_vertex(&self, x: usize, y: usize) -> Vec3 {
+        self.exterior[y * self.width + x].clone()
+    }
+
+    /// Get the vertex on the interior wall at (x, y)
+    fn get_interior_vertex(&self, x: usize, y: usize) -> Vec3 {
+        self.interior[y * self.width + x].clone()
+    }
+
+    /// Add the exterior and interior quads between columns `left` and `right` of rows y - 1
+    /// and y. `right` wraps back to column 0 for the seam.
+    fn add_quads(&mut self, left: usize, right: usize, y: usize) {
+        let tl = self.get_vertex(left, y - 1);
+        let bl = self.get_vertex(left, y);
+        let tr = self.get_vertex(right, y - 1);
+        let br = self.get_vertex(right, y);
+        self.tris
+            .extend_from_slice(&[tl.clone(), bl, br.clone(), tl, br, tr]);
+
+        let tl = self.get_interior_vertex(left, y - 1);
+        let bl = self.get_interior_vertex(left, y);
+        let tr = self.get_interior_vertex(right, y - 1);
+        let br = self.get_interior_vertex(right, y);
+        self.tris
+            .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
+    }
+
+    /// Join the exterior and interior walls between columns `left` and `right` at the rim of
+    /// the opening and at the equator
+    fn add_rings(&mut self, left: usize, right: usize) {
+        let last = self.height - 1;
+        let tl = self.get_interior_vertex(left, last);
+        let bl = self.get_vertex(left, last);
+        let tr = self.get_interior_vertex(right, last);
+        let br = self.get_vertex(right, last);
+        self.tris
+            .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
+
+        let tl = self.get_vertex(left, 0);
+        let bl = self.get_interior_vertex(left, 0);
+        let tr = self.get_vertex(right, 0);
+        let br = self.get_interior_vertex(right, 0);
+        self.tris
+            .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl]);
+    }
+}
+
+impl Default for DomeMeshGenerator {
+    fn default() -> Self {
+        Self {
+            scaling: 1.0,
+            radius: 0.0,
+            opening: 15f32.to_radians(),
+            width: 0,
+            height: 0,
+            heights: vec![],
+            tris: vec![],
+            exterior: vec![],
+            interior: vec![],
+        }
+    }
+}
+
+impl LithophaneGenerator for DomeMeshGenerator {
+    fn generate_with_progress(
+        mut self,
+        source: LightMap,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<Mesh, GenError> {
+        source.validate()?;
+        if self.radius <= 0.0 {
+            return Err(GenError::EmptyDimensions("radius"));
+        }
+
+        let (width, height) = source.dims;
+        let scaling = self.clamped_scaling();
+        self.heights = source.lightnesses.iter().map(|l| l * scaling).collect();
+        self.width = width;
+        self.height = height;
+        self.build_vertices();
+
+        for y in 1..height {
+            for x in 1..width {
+                self.add_quads(x - 1, x, y);
+            }
+            // Close the seam between the last column and the first
+            self.add_quads(width - 1, 0, y);
+            progress(y as f32 / height as f32);
+        }
+
+        for x in 1..width {
+            self.add_rings(x - 1, x);
+        }
+        self.add_rings(width - 1, 0);
+        progress(1.0);
+
+        Ok(Mesh::new(self.tris))
+    }
+}
@@ -0,0 +1,166 @@
+use crate::geo::{Mesh, Vec3};
+
+use super::{GenError, LightMap, LithophaneGenerator};
+
+/// Number of vertices around each dot
+const SEGMENTS: usize = 12;
+/// Number of rings between the base and the tip of each dot
+const RINGS: usize = 4;
+
+/// Halftone-style generator that places one rounded dot per sample on a flat plate, with the
+/// height of each dot encoding the darkness of its pixel.
+pub struct DotMeshGenerator {
+    scaling: f32,
+    radius: f32,
+    base: f32,
+    tris: Vec<Vec3>,
+}
+
+impl DotMeshGenerator {
+    /// Set the height of the dot for a pure black pixel, above the plate. White pixels get a
+    /// flat dot. See [`LithophaneGenerator`] for how this is shared between generators.
+    pub fn scaling(mut self, scaling: f32) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Set the radius of each dot, where neighboring samples are 1 unit apart
+    pub fn dot_relief(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Set the thickness of the plate the dots sit on
+    pub fn base(mut self, base: f32) -> Self {
+        self.base = base;
+        self
+    }
+
+    /// Get the (base, surface) z values of the relief: the bottom of the plate and the tip of
+    /// the dot over a pure black pixel
+    pub fn relief_range(&self) -> (f32, f32) {
+        (0.0, self.base + self.scaling)
+    }
+
+    /// Estimate how many triangles the mesh for a light map of size `dims` will have
+    pub fn estimated_triangles(&self, dims: (usize, usize)) -> usize {
+        // The bottom disc, skirt, dome rings and tip of each segment, plus the plate
+        let per_dot = SEGMENTS * (1 + 2 + 2 * (RINGS - 1) + 1);
+        dims.0 * dims.1 * per_dot + 12
+    }
+
+    /// Get the point on ring `ring` of a dot at angle `segment`
+    fn dot_vertex(&self, x: f32, y: f32, height: f32, ring: usize, segment: usize) -> Vec3 {
+        let phi = ring as f32 / RINGS as f32 * std::f32::consts::FRAC_PI_2;
+        let theta = segment as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let r = self.radius * phi.cos();
+        Vec3 {
+            x: x + r * theta.cos(),
+            y: y + r * theta.sin(),
+            z: self.base + height * phi.sin(),
+        }
+    }
+
+    /// Add a closed dot centered at (x, y). The dot reaches down to z = 0 so it is anchored
+    /// inside the plate.
+    fn add_dot(&mut self, x: f32, y: f32, height: f32) {
+        let floor = Vec3 { x, y, z: 0.0 };
+        let tip = Vec3 {
+            x,
+            y,
+            z: self.base + height,
+        };
+        for j in 0..SEGMENTS {
+            let k = (j + 1) % SEGMENTS;
+            let ring = |r: usize, s: usize| self.dot_vertex(x, y, height, r, s);
+            let skirt = |s: usize| Vec3 {
+                z: 0.0,
+                ..ring(0, s)
+            };
+
+            // Bottom disc and the skirt up to the top of the plate
+            let (a, b) = (skirt(j), skirt(k));
+            let (c, d) = (ring(0, k), ring(0, j));
+            let mut tris = vec![floor.clone(), b.clone(), a.clone()];
+            tris.extend_from_slice(&[a.clone(), b, c.clone(), a, c, d]);
+
+            // The dome itself
+            for r in 0..RINGS - 1 {
+                let (a, b) = (ring(r, j), ring(r, k));
+                let (c, d) = (ring(r + 1, k), ring(r + 1, j));
+                tris.extend_from_slice(&[a.clone(), b, c.clone(), a, c, d]);
+            }
+            tris.extend_from_slice(&[ring(RINGS - 1, j), ring(RINGS - 1, k), tip.clone()]);
+
+            self.tris.extend(tris);
+        }
+    }
+
+    /// Add the plate spanning from `min` to `max` in x and y
+    fn add_plate(&mut self, min: (f32, f32), max: (f32, f32)) {
+        let corner = |i: usize| Vec3 {
+            x: if i & 1 == 0 { min.0 } else { max.0 },
+            y: if i & 2 == 0 { min.1 } else { max.1 },
+            z: if i & 4 == 0 { 0.0 } else { self.base },
+        };
+        // Each face as a quad wound counter-clockwise when seen from outside
+        let faces = [
+            [0, 2, 3, 1],
+            [4, 5, 7, 6],
+            [0, 1, 5, 4],
+            [2, 6, 7, 3],
+            [0, 4, 6, 2],
+            [1, 3, 7, 5],
+        ];
+        for [a, b, c, d] in faces {
+            self.tris.extend_from_slice(&[
+                corner(a),
+                corner(b),
+                corner(c),
+                corner(a),
+                corner(c),
+                corner(d),
+            ]);
+        }
+    }
+}
+
+impl Default for DotMeshGenerator {
+    fn default() -> Self {
+        Self {
+            scaling: 1.0,
+            radius: 0.45,
+            base: 1.0,
+            tris: vec![],
+        }
+    }
+}
+
+impl LithophaneGenerator for DotMeshGenerator {
+    fn generate_with_progress(
+        mut self,
+        source: LightMap,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<Mesh, GenError> {
+        source.validate()?;
+
+        let (width, height) = source.dims;
+        let margin = self.radius.max(0.5);
+        self.add_plate(
+            (-margin, -margin),
+            ((width - 1) as f32 + margin, (height - 1) as f32 + margin),
+        );
+
+        for y in 0..height {
+            for x in 0..width {
+                // Darker pixels get taller dots
+                let h = (1.0 - source.lightnesses[y * width + x]) * self.scaling;
+                self.add_dot(x as f32, y as f32, h);
+            }
+            progress((y + 1) as f32 / height as f32);
+        }
+
+        progress(1.0);
+        Ok(Mesh::new(self.tris))
+    }
+}
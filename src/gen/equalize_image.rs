@@ -0,0 +1,127 @@
+use crate::img::LuminanceWeights;
+
+use super::{
+    fitted_dims, histogram_bin, standard_image::StandardImagePreprocessor, ImagePreprocessor,
+    LightMap,
+};
+
+/// Number of histogram bins the lightnesses are sorted into
+const BINS: usize = 256;
+
+/// Image preprocessor that equalizes the histogram of the light map, spreading a narrow range
+/// of tones over the full relief depth
+pub struct EqualizeImagePreprocessor {
+    width: usize,
+    strength: f32,
+    /// Converts the pixels to lightness before the histogram is taken
+    standard: StandardImagePreprocessor,
+}
+
+impl EqualizeImagePreprocessor {
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Get the (width, height) of the light map produced for an image of the given size
+    pub fn output_dims(&self, width: u32, height: u32) -> (usize, usize) {
+        fitted_dims(width, height, (self.width, 0))
+    }
+
+    /// Set the gamma applied to each pixel in linear light, before the luminance is computed.
+    /// See [`StandardImagePreprocessor::linear_gamma`].
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.standard = self.standard.linear_gamma(gamma);
+        self
+    }
+
+    /// Multiply the relief of each pixel by its opacity, so transparent areas are carved
+    /// thinner. See [`StandardImagePreprocessor::alpha_relief`].
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.standard = self.standard.alpha_relief(enabled);
+        self
+    }
+
+    /// Map luminance to perceived lightness before it becomes relief, which is on by default.
+    /// See [`StandardImagePreprocessor::perceptual`].
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.standard = self.standard.perceptual(enabled);
+        self
+    }
+
+    /// Swap light and dark so bright areas get the thick relief, for front-lit pieces. The
+    /// lightness is inverted before the histogram is taken, so the inverted tones are the ones
+    /// spread out. See [`StandardImagePreprocessor::invert`].
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.standard = self.standard.invert(enabled);
+        self
+    }
+
+    /// Drop pixels more transparent than `threshold` to the base. See
+    /// [`StandardImagePreprocessor::alpha_threshold`].
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.standard = self.standard.alpha_threshold(threshold);
+        self
+    }
+
+    /// Set the lightness of pixels below the alpha threshold. See
+    /// [`StandardImagePreprocessor::transparent_lightness`].
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.standard = self.standard.transparent_lightness(lightness);
+        self
+    }
+
+    /// Set the weights used to sum each pixel's channels into luminance. See
+    /// [`LuminanceWeights`].
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.standard = self.standard.weights(weights);
+        self
+    }
+
+    /// Blend between the original lightness at 0.0 and the fully equalized lightness at 1.0,
+    /// the default
+    pub fn strength(mut self, strength: f32) -> Self {
+        self.strength = strength.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Default for EqualizeImagePreprocessor {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            strength: 1.0,
+            standard: StandardImagePreprocessor::default(),
+        }
+    }
+}
+
+impl ImagePreprocessor for EqualizeImagePreprocessor {
+    fn transform(mut self, image: &image::DynamicImage) -> LightMap {
+        let mut map = std::mem::take(&mut self.standard)
+            .width(self.width)
+            .transform(image);
+
+        let cdf: Vec<usize> = map
+            .histogram(BINS)
+            .into_iter()
+            .scan(0, |total, count| {
+                *total += count as usize;
+                Some(*total)
+            })
+            .collect();
+
+        // Map the darkest tone present to 0.0 and the lightest to 1.0. An image of a single
+        // tone has nothing to spread out.
+        let first = cdf.iter().copied().find(|&c| c > 0).unwrap_or(0);
+        let total = map.lightnesses.len();
+        if total == first {
+            return map;
+        }
+        for l in &mut map.lightnesses {
+            let equalized = (cdf[histogram_bin(*l, BINS)] - first) as f32 / (total - first) as f32;
+            *l += (equalized - *l) * self.strength;
+        }
+        map
+    }
+}
@@ -1,11 +1,13 @@
-use crate::img::{luminance_to_lightness, srgb_to_luminance};
+use crate::img::{LightnessConversion, LuminanceWeights};
 
-use super::{ImagePreprocessor, LightMap};
+use super::{fitted_dims, resize_bounds, ImagePreprocessor, LightMap};
 use image::imageops::FilterType;
 
 pub struct FilterImagePreprocessor {
     width: usize,
+    height: usize,
     filter: FilterType,
+    conversion: LightnessConversion,
 }
 
 impl FilterImagePreprocessor {
@@ -14,33 +16,92 @@ impl FilterImagePreprocessor {
         self
     }
 
+    /// Resize the image to at most `height` pixels tall, keeping its aspect ratio. With a
+    /// width set as well, the image is fit within both. With neither set, the image keeps its
+    /// own size.
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Get the (width, height) of the light map produced for an image of the given size
+    pub fn output_dims(&self, width: u32, height: u32) -> (usize, usize) {
+        fitted_dims(width, height, (self.width, self.height))
+    }
+
     pub fn filter(mut self, filter: FilterType) -> Self {
         self.filter = filter;
         self
     }
+
+    /// Set the gamma applied to each pixel in linear light, before the luminance is computed.
+    /// See [`LightnessConversion`] for the full order of operations.
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.conversion = self.conversion.linear_gamma(gamma);
+        self
+    }
+
+    /// Multiply the relief of each pixel by its opacity, so transparent areas are carved
+    /// thinner. See [`LightnessConversion`] for the exact formula.
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.alpha_relief(enabled);
+        self
+    }
+
+    /// Map luminance to perceived lightness before it becomes relief, which is on by default.
+    /// See [`LightnessConversion::perceptual`].
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.perceptual(enabled);
+        self
+    }
+
+    /// Swap light and dark so bright areas get the thick relief, for front-lit pieces. See
+    /// [`LightnessConversion::invert`].
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.invert(enabled);
+        self
+    }
+
+    /// Drop pixels more transparent than `threshold` to the base. See
+    /// [`LightnessConversion::alpha_threshold`].
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.conversion = self.conversion.alpha_threshold(threshold);
+        self
+    }
+
+    /// Set the lightness of pixels below the alpha threshold. See
+    /// [`LightnessConversion::transparent_lightness`].
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.conversion = self.conversion.transparent_lightness(lightness);
+        self
+    }
+
+    /// Set the weights used to sum each pixel's channels into luminance. See
+    /// [`LuminanceWeights`].
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.conversion = self.conversion.weights(weights);
+        self
+    }
 }
 
 impl Default for FilterImagePreprocessor {
     fn default() -> Self {
         Self {
             width: 0,
+            height: 0,
             filter: FilterType::CatmullRom,
+            conversion: LightnessConversion::default(),
         }
     }
 }
 
 impl ImagePreprocessor for FilterImagePreprocessor {
     fn transform(self, image: &image::DynamicImage) -> LightMap {
-        let image = image.resize(self.width as u32, image.height(), self.filter);
-        let lights: Vec<_> = image
-            .to_rgb8()
-            .chunks_exact(3)
-            .map(srgb_to_luminance)
-            .map(luminance_to_lightness)
-            .map(|l| l / 100.0)
-            .collect();
+        let (width, height) =
+            resize_bounds(image.width(), image.height(), (self.width, self.height));
+        let image = image.resize(width, height, self.filter);
         LightMap {
-            lightnesses: lights,
+            lightnesses: self.conversion.lightnesses(&image),
             dims: (image.width() as usize, image.height() as usize),
         }
     }
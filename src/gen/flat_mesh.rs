@@ -1,13 +1,34 @@
+use image::GrayImage;
+
 use crate::geo::{Mesh, Vec3};
 
-use super::{LightMap, LithophaneGenerator};
+use super::{morton_order, GenError, LightMap, LithophaneGenerator, ReliefProfile, TriangleOrder};
 
 pub struct FlatMeshGenerator {
     scaling: f32,
     width: usize,
+    height: usize,
     heights: Vec<f32>,
     tris: Vec<Vec3>,
     bottom: f32,
+    order: TriangleOrder,
+    draft: f32,
+    subdivisions: usize,
+    spacing: f32,
+    light: (f32, f32),
+    gradient: f32,
+    cutout: Option<f32>,
+    depth_mask: Option<GrayImage>,
+    min_feature: f32,
+    profile: ReliefProfile,
+    bevel: Option<f32>,
+    mirrored_back: bool,
+    edge_ramp: usize,
+    min_thickness: f32,
+    base_thickness: f32,
+    invert: bool,
+    border: Option<(usize, f32)>,
+    hang_hole: Option<(f32, f32)>,
 }
 
 enum Side {
@@ -18,44 +39,474 @@ enum Side {
 }
 
 impl FlatMeshGenerator {
-    /// Set the scale multiplier for the generator to use.
+    /// Set the relief depth, from the back of the lithophane to the surface over a pure black
+    /// pixel. See [`LithophaneGenerator`] for how this is shared between generators.
+    ///
+    /// This is the raw multiplier applied to lightness and the original way to size the
+    /// relief. For a print of a known thickness, use [`max_thickness`](Self::max_thickness)
+    /// and [`base_thickness`](Self::base_thickness) instead.
     pub fn scaling(mut self, scaling: f32) -> Self {
         // Negative scaling makes the lithophane work normally
         self.scaling = -scaling;
         self
     }
 
+    /// Set the order the triangles are emitted in. `Grouped` and `Morton` emit the whole relief
+    /// surface first, followed by the brim walls and the bottom.
+    pub fn order(mut self, order: TriangleOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Angle the brim walls outward by `degrees` from vertical, so the base footprint is
+    /// larger than the relief surface. The angle is measured over the full relief depth.
+    pub fn draft_angle(mut self, degrees: f32) -> Self {
+        self.draft = degrees;
+        self
+    }
+
+    /// Set the distance between neighboring pixels in the output, in millimeters. Together
+    /// with the preprocessor width this sets the physical size of the lithophane; the
+    /// thickness is set independently.
+    pub fn pixel_size(mut self, mm: f32) -> Self {
+        self.spacing = mm;
+        self
+    }
+
+    /// Set how much thicker a pure black pixel is than a pure white one, in millimeters. The
+    /// relief sits on top of the [`base_thickness`](Self::base_thickness), so white pixels are
+    /// the base thickness and black pixels the base plus `mm`. Replaces `scaling`.
+    pub fn max_thickness(self, mm: f32) -> Self {
+        self.scaling(mm)
+    }
+
+    /// Put a solid backing `mm` thick under the relief, so even pure white pixels are that
+    /// thick. The relief depth is unchanged, so the whole lithophane gets `mm` thicker.
+    pub fn base_thickness(mut self, mm: f32) -> Self {
+        self.base_thickness = mm;
+        self
+    }
+
+    /// Shorthand for [`base_thickness`](Self::base_thickness)
+    pub fn base(self, thickness: f32) -> Self {
+        self.base_thickness(thickness)
+    }
+
+    /// Smooth the relief surface with `levels` rounds of Catmull-Clark subdivision before it is
+    /// triangulated. Each level doubles the grid resolution in both directions while keeping
+    /// the same footprint. The edge of the surface is treated as a crease, so the brim stays
+    /// attached to the outline of the relief.
+    pub fn subdivide_smooth(mut self, levels: usize) -> Self {
+        self.subdivisions = levels;
+        self
+    }
+
+    /// Thicken the base towards `light_xy` to even out the brightness of a backlight that isn't
+    /// uniform. `light_xy` is given as a fraction of the image size, with (0, 0) at the top-left
+    /// corner. The base is `strength` thicker directly over the light and tapers linearly to
+    /// its normal thickness at the farthest corner.
+    pub fn base_gradient(mut self, light_xy: (f32, f32), strength: f32) -> Self {
+        self.light = light_xy;
+        self.gradient = strength;
+        self
+    }
+
+    /// Only keep the parts of the relief thicker than `level`, given as a fraction of the full
+    /// relief depth, and remove everything else. The result is a silhouette of the subject with
+    /// vertical walls and a flat back, like a sticker. The triangle order and draft angle are
+    /// ignored in this mode.
+    pub fn threshold_cutout(mut self, level: f32) -> Self {
+        self.cutout = Some(level);
+        self
+    }
+
+    /// Weight the relief depth of each pixel by a grayscale mask, so white areas get the full
+    /// `scaling` and black areas stay flat at the top surface. The mask should cover the same
+    /// area as the source image and is resized to match the light map.
+    pub fn depth_mask(mut self, mask: GrayImage) -> Self {
+        self.depth_mask = Some(mask);
+        self
+    }
+
+    /// Remove bumps and pits narrower than `mm` from the relief, such as details smaller than
+    /// the nozzle that would only print as noise. This is a morphological open followed by a
+    /// close over a square window sized from `pixel_size`.
+    pub fn min_feature(mut self, mm: f32) -> Self {
+        self.min_feature = mm;
+        self
+    }
+
+    /// Set how lightness is turned into relief depth. `Stepped` gives logos crisp flat levels.
+    pub fn relief_profile(mut self, profile: ReliefProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Render the relief as terraces between contour lines spaced `interval` apart in
+    /// lightness, for topographic-style art. Shorthand for `ReliefProfile::Contour`.
+    pub fn contour(self, interval: f32) -> Self {
+        self.relief_profile(ReliefProfile::Contour(interval))
+    }
+
+    /// Soften cells where the relief jumps by more than `min_jump` between neighboring samples,
+    /// such as the edges between the levels of a `Stepped` profile. These cells get a vertex in
+    /// the middle at the average of their corners and are split into four triangles, so the
+    /// slope no longer depends on which way the cell's diagonal runs and diagonal edges don't
+    /// print as a staircase of cliffs. `min_jump` is in the same units as `scaling`.
+    pub fn bevel_steps(mut self, min_jump: f32) -> Self {
+        self.bevel = Some(min_jump);
+        self
+    }
+
+    /// Fade the relief out over the outermost `pixels` samples, ramping it linearly down to
+    /// the base (or the minimum thickness) at the very edge. Images whose subject runs off the
+    /// edge then end in a clean bevel instead of being cut off abruptly at the brim wall.
+    pub fn edge_ramp(mut self, pixels: usize) -> Self {
+        self.edge_ramp = pixels;
+        self
+    }
+
+    /// Keep the surface at least `t` above the back everywhere, so the lightest pixels don't
+    /// print as holes. Only pixels thinner than `t` are raised, which flattens the lightest
+    /// tones together; the depth over dark pixels is still set by `scaling`. The thickness
+    /// includes the base, so a `t` of `base_thickness` or less changes nothing and a `t` of
+    /// `scaling + base_thickness` or more flattens the whole surface. `t` is in the same units
+    /// as `scaling`.
+    pub fn min_thickness(mut self, t: f32) -> Self {
+        self.min_thickness = t;
+        self
+    }
+
+    /// Raise the light areas instead of the dark ones, for a positive relief that reads under
+    /// front lighting, such as signage. The base and walls are unchanged.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Surround the relief with a flat frame `width_px` samples wide, standing `height` above
+    /// the surface over a pure black pixel. The frame is added after the edge ramp and before
+    /// subdivision, and shares the brim and back of the relief.
+    pub fn border(mut self, width_px: usize, height: f32) -> Self {
+        self.border = Some((width_px, height));
+        self
+    }
+
+    /// Punch a round hole of `radius` mm through the lithophane for hanging it, centered
+    /// horizontally with its center `margin` mm below the top edge (y = 0). Put it in a
+    /// [`border`](Self::border) to keep it clear of the image. The hole is cut along the
+    /// sample grid, so its wall steps unless the pixel size is small next to the radius. Like
+    /// a threshold cutout, this ignores the triangle order and draft angle.
+    pub fn hang_hole(mut self, radius: f32, margin: f32) -> Self {
+        self.hang_hole = Some((radius, margin));
+        self
+    }
+
+    /// Give the back of the lithophane a relief of its own, mirrored left to right from the
+    /// front, instead of leaving it flat. The back relief is as deep as the front, so the piece
+    /// is symmetric about the usual base plane and twice as thick where the image is dark. This
+    /// suits hanging signs that are lit and seen from both sides.
+    pub fn mirrored_back(mut self, enabled: bool) -> Self {
+        self.mirrored_back = enabled;
+        self
+    }
+
+    /// Get the (base, surface) z values of the relief: the back of the lithophane and the
+    /// surface over a pure black pixel. White pixels sit `base_thickness` above the back, or
+    /// at the top of the relief when inverted, which drops black to the base thickness. The
+    /// base gradient and depth mask are not taken into account.
+    pub fn relief_range(&self) -> (f32, f32) {
+        let surface = if self.invert { self.scaling } else { 0.0 };
+        (self.scaling - self.base_thickness, surface)
+    }
+
+    /// Estimate how many triangles the mesh for a light map of size `dims` will have. Beveled
+    /// steps add two more triangles per softened cell. With a threshold cutout or hang hole the
+    /// back is built cell by cell and every cell is counted as kept; each removed cell drops
+    /// four triangles and each side of it next to a kept cell adds two for the wall.
+    pub fn estimated_triangles(&self, dims: (usize, usize)) -> usize {
+        let (mut width, mut height) = dims;
+        if let Some((px, _)) = self.border {
+            (width, height) = (width + 2 * px, height + 2 * px);
+        }
+        for _ in 0..self.subdivisions {
+            (width, height) = (width * 2 - 1, height * 2 - 1);
+        }
+        let cells = width.saturating_sub(1) * height.saturating_sub(1);
+        let brim = 4 * (width.saturating_sub(1) + height.saturating_sub(1));
+        let bottom = if self.has_flat_bottom() && !self.cuts_cells() {
+            2 * (width.saturating_sub(1) + height.saturating_sub(1))
+        } else {
+            2 * cells
+        };
+        2 * cells + brim + bottom
+    }
+
+    /// Generate the mesh and export it as a binary PLY, with each vertex colored by the
+    /// lightness of the source under it. Vertices on the brim and bottom take the color of the
+    /// nearest edge pixel. Useful for checking how lightness ends up as relief.
+    pub fn generate_ply(self, source: LightMap) -> Result<Vec<u8>, GenError> {
+        let spacing = self.spacing;
+        // The relief starts inside the border
+        let offset = self.border.map_or(0.0, |(px, _)| px as f32);
+        let colors = source.clone();
+        Ok(self.generate(source)?.as_ply_bytes(|v| {
+            colors.sample_bilinear(v.x / spacing - offset, v.y / spacing - offset)
+        }))
+    }
+
     /// Generate a heightmap for the current source and save it to `self.heights`
     fn generate_heightmap(&mut self, source: LightMap) {
-        self.heights.reserve(source.dims.0 * source.dims.1);
+        let (width, height) = source.dims;
+        self.heights.reserve(width * height);
+
+        let weights: Vec<f32> = match self.depth_mask {
+            Some(ref mask) => {
+                let resized;
+                let mask = if mask.dimensions() == (width as u32, height as u32) {
+                    mask
+                } else {
+                    resized = image::imageops::resize(
+                        mask,
+                        width as u32,
+                        height as u32,
+                        image::imageops::FilterType::Triangle,
+                    );
+                    &resized
+                };
+                mask.pixels().map(|p| p.0[0] as f32 / 255.0).collect()
+            }
+            None => vec![1.0; width * height],
+        };
 
         // Calculate the percieved lightness of each pixel and scale to get the final heightmap
         source
             .lightnesses
             .iter()
-            .map(|l| l * self.scaling)
+            .zip(weights)
+            .map(|(l, w)| {
+                // Inverting raises the light areas instead of the dark ones
+                let l = if self.invert { 1.0 - l } else { *l };
+                l * self.scaling * w
+            })
             .for_each(|h| {
                 self.heights.push(h);
             });
 
-        self.bottom = 1.0 * self.scaling;
+        // The backing goes under the relief, leaving the top surface where it was
+        self.bottom = self.scaling - self.base_thickness;
+
+        if self.min_thickness > 0.0 {
+            let floor = self.bottom + self.min_thickness;
+            self.heights.iter_mut().for_each(|h| *h = h.max(floor));
+        }
+    }
+
+    /// Replace each height with the minimum or maximum in a square window of radius `r`
+    fn morph(&mut self, r: usize, pick: fn(f32, f32) -> f32) {
+        let (w, h) = (self.width, self.height);
+        let window = |i: usize, max: usize| i.saturating_sub(r)..(i + r + 1).min(max);
+
+        let rows: Vec<f32> = (0..w * h)
+            .map(|i| {
+                let (x, y) = (i % w, i / w);
+                window(x, w)
+                    .map(|x| self.heights[y * w + x])
+                    .reduce(pick)
+                    .unwrap()
+            })
+            .collect();
+        self.heights = (0..w * h)
+            .map(|i| {
+                let (x, y) = (i % w, i / w);
+                window(y, h).map(|y| rows[y * w + x]).reduce(pick).unwrap()
+            })
+            .collect();
+    }
+
+    /// Open and then close the heightmap to remove features smaller than `min_feature`
+    fn filter_small_features(&mut self) {
+        let pixels = self.min_feature / self.spacing;
+        let r = ((pixels - 1.0) / 2.0).round().max(0.0) as usize;
+        if r == 0 {
+            return;
+        }
+        // Opening removes narrow bumps, closing fills narrow pits
+        self.morph(r, f32::min);
+        self.morph(r, f32::max);
+        self.morph(r, f32::max);
+        self.morph(r, f32::min);
+    }
+
+    /// Scale the relief down linearly towards the edges of the heightmap over `edge_ramp`
+    /// samples
+    fn ramp_edges(&mut self) {
+        if self.edge_ramp == 0 {
+            return;
+        }
+        let (w, h) = (self.width, self.height);
+        let floor = self.bottom + self.base_thickness.max(self.min_thickness);
+        for y in 0..h {
+            for x in 0..w {
+                let distance = x.min(y).min(w - 1 - x).min(h - 1 - y);
+                if distance < self.edge_ramp {
+                    let t = distance as f32 / self.edge_ramp as f32;
+                    let height = &mut self.heights[y * w + x];
+                    *height = floor + (*height - floor) * t;
+                }
+            }
+        }
+    }
+
+    /// Pad the heightmap with the border frame on every side
+    fn add_border(&mut self) {
+        let Some((px, raise)) = self.border else {
+            return;
+        };
+        let width = self.width + 2 * px;
+        let mut heights = vec![raise; width * (self.height + 2 * px)];
+        for (y, row) in self.heights.chunks_exact(self.width).enumerate() {
+            let start = (y + px) * width + px;
+            heights[start..start + self.width].copy_from_slice(row);
+        }
+        self.heights = heights;
+        self.width = width;
+        self.height += 2 * px;
+    }
+
+    /// Apply one level of Catmull-Clark subdivision to the heightmap.
+    ///
+    /// On a regular grid the x and y positions of the subdivided points land exactly halfway
+    /// between the old ones, so only the heights need to be computed.
+    fn subdivide_heightmap(&mut self) {
+        let (w, h) = (self.width, self.height);
+        if w < 2 || h < 2 {
+            return;
+        }
+        let at = |x: usize, y: usize| self.heights[y * w + x];
+        let face =
+            |x: usize, y: usize| (at(x, y) + at(x + 1, y) + at(x, y + 1) + at(x + 1, y + 1)) / 4.0;
+
+        let (nw, nh) = (w * 2 - 1, h * 2 - 1);
+        let mut heights = vec![0.0; nw * nh];
+        for y in 0..nh {
+            for x in 0..nw {
+                let (cx, cy) = (x / 2, y / 2);
+                heights[y * nw + x] = match (x % 2, y % 2) {
+                    (1, 1) => face(cx, cy),
+                    // Point on a horizontal edge
+                    (1, 0) => {
+                        let mid = (at(cx, cy) + at(cx + 1, cy)) / 2.0;
+                        if cy == 0 || cy == h - 1 {
+                            mid
+                        } else {
+                            (mid + (face(cx, cy - 1) + face(cx, cy)) / 2.0) / 2.0
+                        }
+                    }
+                    // Point on a vertical edge
+                    (0, 1) => {
+                        let mid = (at(cx, cy) + at(cx, cy + 1)) / 2.0;
+                        if cx == 0 || cx == w - 1 {
+                            mid
+                        } else {
+                            (mid + (face(cx - 1, cy) + face(cx, cy)) / 2.0) / 2.0
+                        }
+                    }
+                    // Original vertex
+                    _ => {
+                        let p = at(cx, cy);
+                        let x_edge = cx == 0 || cx == w - 1;
+                        let y_edge = cy == 0 || cy == h - 1;
+                        if x_edge && y_edge {
+                            p
+                        } else if x_edge {
+                            (at(cx, cy - 1) + 6.0 * p + at(cx, cy + 1)) / 8.0
+                        } else if y_edge {
+                            (at(cx - 1, cy) + 6.0 * p + at(cx + 1, cy)) / 8.0
+                        } else {
+                            let faces = (face(cx - 1, cy - 1)
+                                + face(cx, cy - 1)
+                                + face(cx - 1, cy)
+                                + face(cx, cy))
+                                / 4.0;
+                            let edges =
+                                (at(cx - 1, cy) + at(cx + 1, cy) + at(cx, cy - 1) + at(cx, cy + 1))
+                                    / 8.0
+                                    + p / 2.0;
+                            (faces + 2.0 * edges + p) / 4.0
+                        }
+                    }
+                };
+            }
+        }
+
+        self.heights = heights;
+        self.width = nw;
+        self.height = nh;
+        self.spacing /= 2.0;
     }
 
     /// Get the vertex at (x, y, heights[x, y])
     fn get_vertex(&self, x: usize, y: usize) -> Vec3 {
         Vec3 {
-            x: x as f32,
-            y: y as f32,
+            x: x as f32 * self.spacing,
+            y: y as f32 * self.spacing,
             z: self.heights[y * self.width + x],
         }
     }
 
-    /// Get the vertex at (x, y, heights.min())
+    /// Whether some cells are removed by a threshold cutout or hang hole, which meshes the
+    /// lithophane cell by cell
+    fn cuts_cells(&self) -> bool {
+        self.cutout.is_some() || self.hang_hole.is_some()
+    }
+
+    /// Whether the back is a single flat quad rather than a grid following the base
+    fn has_flat_bottom(&self) -> bool {
+        self.gradient == 0.0 && !self.mirrored_back
+    }
+
+    /// Get the z of the base at (x, y), lowered towards the light by the base gradient and by
+    /// the mirrored relief
+    fn get_bottom_z(&self, x: usize, y: usize) -> f32 {
+        let mirror = if self.mirrored_back {
+            self.heights[y * self.width + self.width - 1 - x] - self.bottom - self.base_thickness
+        } else {
+            0.0
+        };
+        if self.gradient == 0.0 {
+            return self.bottom - mirror;
+        }
+        let (lx, ly) = self.light;
+        let u = x as f32 / (self.width - 1).max(1) as f32;
+        let v = y as f32 / (self.height - 1).max(1) as f32;
+        let distance = |cx: f32, cy: f32| ((cx - lx).powi(2) + (cy - ly).powi(2)).sqrt();
+        let farthest = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)]
+            .iter()
+            .map(|&(cx, cy)| distance(cx, cy))
+            .fold(0.0, f32::max);
+        let falloff = (1.0 - distance(u, v) / farthest).max(0.0);
+        self.bottom - self.gradient * falloff - mirror
+    }
+
+    /// Get the vertex at (x, y, heights.min()), pushed outward by the draft if on the edge
     fn get_bottom_vertex(&self, x: usize, y: usize) -> Vec3 {
+        let z = self.get_bottom_z(x, y);
+        // The top of the relief is always at z = 0, so the wall spans the whole bottom depth
+        let offset = self.draft.to_radians().tan() * z.abs();
+        let shift = |v: usize, max: usize| {
+            if v == 0 {
+                -offset
+            } else if v == max - 1 {
+                offset
+            } else {
+                0.0
+            }
+        };
         Vec3 {
-            x: x as f32,
-            y: y as f32,
-            z: self.bottom,
+            x: x as f32 * self.spacing + shift(x, self.width),
+            y: y as f32 * self.spacing + shift(y, self.height),
+            z,
         }
     }
 
@@ -65,6 +516,33 @@ impl FlatMeshGenerator {
         let bl = self.get_vertex(x - 1, y);
         let tr = self.get_vertex(x, y - 1);
         let br = self.get_vertex(x, y);
+
+        let corners = [&tl, &tr, &br, &bl].map(|v| v.z);
+        let jump = corners.iter().fold(f32::MIN, |a, &b| a.max(b))
+            - corners.iter().fold(f32::MAX, |a, &b| a.min(b));
+        if self.bevel.is_some_and(|min_jump| jump > min_jump) {
+            let center = Vec3 {
+                x: (tl.x + br.x) / 2.0,
+                y: (tl.y + br.y) / 2.0,
+                z: corners.iter().sum::<f32>() / 4.0,
+            };
+            self.tris.extend_from_slice(&[
+                tl.clone(),
+                tr.clone(),
+                center.clone(),
+                tr,
+                br.clone(),
+                center.clone(),
+                br,
+                bl.clone(),
+                center.clone(),
+                bl,
+                tl,
+                center,
+            ]);
+            return;
+        }
+
         self.tris
             .extend_from_slice(&[br.clone(), bl, tl.clone(), tr, br, tl])
     }
@@ -108,11 +586,129 @@ impl FlatMeshGenerator {
         }
     }
 
+    /// Add the flat bottom as a fan around its center, meeting every vertex along the bottom of
+    /// the brim so the walls and the bottom share their edges exactly
     fn add_bottom(&mut self, width: usize, height: usize) {
         let tl = self.get_bottom_vertex(0, 0);
-        let tr = self.get_bottom_vertex(width - 1, 0);
-        let bl = self.get_bottom_vertex(0, height - 1);
         let br = self.get_bottom_vertex(width - 1, height - 1);
+        let center = Vec3 {
+            x: (tl.x + br.x) / 2.0,
+            y: (tl.y + br.y) / 2.0,
+            z: tl.z,
+        };
+
+        // Walk the edge of the bottom once around, starting and ending at the top left
+        let edge: Vec<(usize, usize)> = (0..width - 1)
+            .map(|x| (x, 0))
+            .chain((0..height - 1).map(|y| (width - 1, y)))
+            .chain((1..width).rev().map(|x| (x, height - 1)))
+            .chain((1..height).rev().map(|y| (0, y)))
+            .collect();
+        for (i, &(x, y)) in edge.iter().enumerate() {
+            let (nx, ny) = edge[(i + 1) % edge.len()];
+            let a = self.get_bottom_vertex(x, y);
+            let b = self.get_bottom_vertex(nx, ny);
+            self.tris.extend_from_slice(&[center.clone(), b, a]);
+        }
+    }
+
+    /// Get the vertex on the back of a cutout under (x, y)
+    fn get_cutout_bottom_vertex(&self, x: usize, y: usize) -> Vec3 {
+        Vec3 {
+            x: x as f32 * self.spacing,
+            y: y as f32 * self.spacing,
+            z: self.get_bottom_z(x, y),
+        }
+    }
+
+    /// Add a vertical wall under the surface edge from `a` to `b`. The wall faces to the right
+    /// of the edge direction when viewed from above.
+    fn add_cutout_wall(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let at = self.get_vertex(a.0, a.1);
+        let bt = self.get_vertex(b.0, b.1);
+        let ab = self.get_cutout_bottom_vertex(a.0, a.1);
+        let bb = self.get_cutout_bottom_vertex(b.0, b.1);
+        self.tris
+            .extend_from_slice(&[at.clone(), ab, bb.clone(), at, bb, bt])
+    }
+
+    /// Get whether each sample survives the threshold cutout and hang hole
+    fn kept_samples(&self) -> Vec<bool> {
+        let threshold = self.cutout.map(|level| level * self.scaling.abs());
+        let white = self.bottom + self.base_thickness;
+        let hole = self.hang_hole.map(|(radius, margin)| {
+            let center_x = (self.width - 1) as f32 * self.spacing / 2.0;
+            (center_x, margin, radius)
+        });
+        self.heights
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let (x, y) = (i % self.width, i / self.width);
+                let thick = threshold.is_none_or(|threshold| h - white > threshold);
+                let outside_hole = hole.is_none_or(|(cx, cy, radius)| {
+                    let (dx, dy) = (x as f32 * self.spacing - cx, y as f32 * self.spacing - cy);
+                    dx.hypot(dy) > radius
+                });
+                thick && outside_hole
+            })
+            .collect()
+    }
+
+    /// Mesh only the cells whose four corners are kept, walling them off from the cells that
+    /// were removed
+    fn add_kept_cells(&mut self, keep: &[bool]) {
+        let (width, height) = (self.width, self.height);
+        let cells: Vec<bool> = (0..(width - 1) * (height - 1))
+            .map(|i| {
+                let (x, y) = (i % (width - 1), i / (width - 1));
+                keep[y * width + x]
+                    && keep[y * width + x + 1]
+                    && keep[(y + 1) * width + x]
+                    && keep[(y + 1) * width + x + 1]
+            })
+            .collect();
+        // Whether the cell whose bottom-right vertex is at (x, y) is kept
+        let kept = |x: usize, y: usize| {
+            x > 0 && y > 0 && x < width && y < height && cells[(y - 1) * (width - 1) + x - 1]
+        };
+
+        for y in 1..height {
+            for x in 1..width {
+                if !kept(x, y) {
+                    continue;
+                }
+                self.add_quad(x, y);
+                let tl = self.get_cutout_bottom_vertex(x - 1, y - 1);
+                let tr = self.get_cutout_bottom_vertex(x, y - 1);
+                let bl = self.get_cutout_bottom_vertex(x - 1, y);
+                let br = self.get_cutout_bottom_vertex(x, y);
+                self.tris
+                    .extend_from_slice(&[tl.clone(), bl, br.clone(), tr, tl, br]);
+
+                if !kept(x, y - 1) {
+                    self.add_cutout_wall((x - 1, y - 1), (x, y - 1));
+                }
+                if !kept(x, y + 1) {
+                    self.add_cutout_wall((x, y), (x - 1, y));
+                }
+                if !kept(x - 1, y) {
+                    self.add_cutout_wall((x - 1, y), (x - 1, y - 1));
+                }
+                if !kept(x + 1, y) {
+                    self.add_cutout_wall((x, y - 1), (x, y));
+                }
+            }
+        }
+    }
+
+    /// Add a quad of the base whose bottom-right vertex is at (x, y). Only needed when the
+    /// base isn't flat.
+    fn add_bottom_quad(&mut self, x: usize, y: usize) {
+        let tl = self.get_bottom_vertex(x - 1, y - 1);
+        let tr = self.get_bottom_vertex(x, y - 1);
+        let bl = self.get_bottom_vertex(x - 1, y);
+        let br = self.get_bottom_vertex(x, y);
         self.tris
             .extend_from_slice(&[tl.clone(), bl, br.clone(), tr, tl, br])
     }
@@ -123,26 +719,93 @@ impl Default for FlatMeshGenerator {
         Self {
             scaling: 1.0,
             width: 0,
+            height: 0,
             heights: vec![],
             tris: vec![],
             bottom: f32::MAX,
+            order: TriangleOrder::default(),
+            draft: 0.0,
+            subdivisions: 0,
+            spacing: 1.0,
+            light: (0.5, 0.5),
+            gradient: 0.0,
+            cutout: None,
+            depth_mask: None,
+            min_feature: 0.0,
+            profile: ReliefProfile::default(),
+            bevel: None,
+            mirrored_back: false,
+            edge_ramp: 0,
+            min_thickness: 0.0,
+            base_thickness: 0.0,
+            invert: false,
+            border: None,
+            hang_hole: None,
         }
     }
 }
 
 impl LithophaneGenerator for FlatMeshGenerator {
-    fn generate(mut self, source: LightMap) -> crate::geo::Mesh {
-        let (width, height) = source.dims;
+    fn generate_with_progress(
+        mut self,
+        mut source: LightMap,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<Mesh, GenError> {
+        source.validate()?;
+        if self.spacing <= 0.0 {
+            return Err(GenError::EmptyDimensions("pixel size"));
+        }
+
+        source.apply_profile(self.profile);
+        self.width = source.dims.0;
+        self.height = source.dims.1;
         self.generate_heightmap(source);
-        self.width = width;
+        self.filter_small_features();
+        self.ramp_edges();
+        self.add_border();
+        for _ in 0..self.subdivisions {
+            self.subdivide_heightmap();
+        }
+        let (width, height) = (self.width, self.height);
 
-        for y in 1..height {
-            for x in 1..width {
-                self.add_quad(x, y);
+        if self.cuts_cells() {
+            let keep = self.kept_samples();
+            self.add_kept_cells(&keep);
+            progress(1.0);
+            return Ok(Mesh::new(self.tris));
+        }
+
+        match self.order {
+            TriangleOrder::RowMajor => {
+                for y in 1..height {
+                    for x in 1..width {
+                        self.add_quad(x, y);
+                    }
+
+                    self.add_brim_quad(0, y, Side::Left);
+                    self.add_brim_quad(width - 1, y, Side::Right);
+                    progress(y as f32 / height as f32);
+                }
             }
+            TriangleOrder::Grouped | TriangleOrder::Morton => {
+                if self.order == TriangleOrder::Morton {
+                    for (x, y) in morton_order(width, height) {
+                        self.add_quad(x, y);
+                    }
+                } else {
+                    for y in 1..height {
+                        for x in 1..width {
+                            self.add_quad(x, y);
+                        }
+                        progress(y as f32 / height as f32);
+                    }
+                }
 
-            self.add_brim_quad(0, y, Side::Left);
-            self.add_brim_quad(width - 1, y, Side::Right);
+                for y in 1..height {
+                    self.add_brim_quad(0, y, Side::Left);
+                    self.add_brim_quad(width - 1, y, Side::Right);
+                }
+            }
         }
 
         for x in 1..self.width {
@@ -150,8 +813,17 @@ impl LithophaneGenerator for FlatMeshGenerator {
             self.add_brim_quad(x, height - 1, Side::Bottom);
         }
 
-        self.add_bottom(width, height);
+        if self.has_flat_bottom() {
+            self.add_bottom(width, height);
+        } else {
+            for y in 1..height {
+                for x in 1..width {
+                    self.add_bottom_quad(x, y);
+                }
+            }
+        }
 
-        Mesh::new(self.tris)
+        progress(1.0);
+        Ok(Mesh::new(self.tris))
     }
 }
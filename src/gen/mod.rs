@@ -2,24 +2,453 @@ use image::DynamicImage;
 
 use crate::geo::Mesh;
 
+/// Image preprocessor that blurs away noise
+pub mod blur_image;
+/// Image preprocessor with gamma and contrast controls
+pub mod contrast_image;
 /// Cylindrical lithophane generator
 pub mod cylinder_mesh;
+/// Hemispherical lithophane generator
+pub mod dome_mesh;
+/// Halftone-style generator with one dot per sample
+pub mod dot_mesh;
+/// Image preprocessor that equalizes the lightness histogram
+pub mod equalize_image;
 /// Image preprocessor with user-specified filter
 pub mod filter_image;
 /// Flat image lithophane generator
 pub mod flat_mesh;
+/// Heightmap preview renderers
+pub mod preview;
+/// QR codes embedded as relief
+#[cfg(feature = "qr")]
+pub mod qr;
 /// Standard image preprocessor
 pub mod standard_image;
+/// Image preprocessor that streams huge images row by row
+pub mod tiled_image;
 
-pub trait LithophaneGenerator {
-    fn generate(self, source: LightMap) -> Mesh;
+/// How lightness is turned into relief depth
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum ReliefProfile {
+    /// Every lightness gets its own depth, for photos
+    #[default]
+    Continuous,
+    /// Round the lightness to the nearest of this many evenly spaced levels, giving crisp flat
+    /// steps suited to logos. Fewer than 2 levels are treated as 2.
+    Stepped(usize),
+    /// Drop the lightness to the multiple of this interval below it, like the bands between
+    /// the contour lines of a topographic map. Each band becomes a flat terrace whose edge
+    /// follows an iso-lightness contour. An interval of zero or less has no effect.
+    Contour(f32),
 }
 
+impl ReliefProfile {
+    /// Map a lightness in \[0, 1] through the profile
+    pub fn apply(self, lightness: f32) -> f32 {
+        match self {
+            ReliefProfile::Continuous => lightness,
+            ReliefProfile::Stepped(levels) => {
+                let steps = levels.max(2) as f32 - 1.0;
+                (lightness.clamp(0.0, 1.0) * steps).round() / steps
+            }
+            ReliefProfile::Contour(interval) if interval > 0.0 => {
+                ((lightness.clamp(0.0, 1.0) / interval).floor() * interval).min(1.0)
+            }
+            ReliefProfile::Contour(_) => lightness,
+        }
+    }
+}
+
+/// Get the bounds to pass to `DynamicImage::resize` to fit an image of `width` x `height`
+/// within `target`, a (width, height) where 0 leaves that side unconstrained. An image with
+/// neither side constrained is left at its own size.
+pub(crate) fn resize_bounds(width: u32, height: u32, target: (usize, usize)) -> (u32, u32) {
+    let bound = |target: usize, own: u32| if target == 0 { own } else { target as u32 };
+    (bound(target.0, width), bound(target.1, height))
+}
+
+/// Get the size of the light map a preprocessor produces when fitting an image of
+/// `width` x `height` within `target`, matching `DynamicImage::resize` with the bounds from
+/// [`resize_bounds`]. Images are only scaled up when both sides are constrained.
+pub(crate) fn fitted_dims(width: u32, height: u32, target: (usize, usize)) -> (usize, usize) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+    let (bound_width, bound_height) = resize_bounds(width, height, target);
+    if (bound_width, bound_height) == (width, height) {
+        return (width as usize, height as usize);
+    }
+    let ratio = (bound_width as f64 / width as f64).min(bound_height as f64 / height as f64);
+    let scale = |v: u32| ((v as f64 * ratio).round() as usize).max(1);
+    (scale(width), scale(height))
+}
+
+/// Get the size of the light map a preprocessor produces when resizing an image of
+/// `width` x `height` to `target_width`, matching `DynamicImage::resize`. Images are never
+/// scaled up.
+pub(crate) fn resized_dims(width: u32, height: u32, target_width: usize) -> (usize, usize) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+    let ratio = (target_width as f64 / width as f64).min(1.0);
+    let scale = |v: u32| ((v as f64 * ratio).round() as usize).max(1);
+    (scale(width), scale(height))
+}
+
+/// Get which of `bins` equal-width bins over [0, 1] lightness `l` falls into, as counted by
+/// [`LightMap::histogram`]. Values outside [0, 1] go in the first or last bin.
+pub(crate) fn histogram_bin(l: f32, bins: usize) -> usize {
+    ((l.clamp(0.0, 1.0) * bins as f32) as usize).min(bins.saturating_sub(1))
+}
+
+/// The order in which a generator emits its triangles. Every order produces the same set of
+/// triangles, only their position in the output changes.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum TriangleOrder {
+    /// Emit triangles row by row, interleaving the different parts of the mesh
+    #[default]
+    RowMajor,
+    /// Emit each part of the mesh (surface, walls, bridges) as one contiguous block
+    Grouped,
+    /// Like `Grouped`, but emit the surface quads along a Morton (Z-order) curve
+    Morton,
+}
+
+/// Get the (x, y) positions of the quads in the grid `1..width` by `1..height` in Morton order
+pub(crate) fn morton_order(width: usize, height: usize) -> Vec<(usize, usize)> {
+    // Spread the bits of v so there is a zero between each of them
+    fn spread(v: usize) -> u64 {
+        let mut v = v as u64 & 0xffff_ffff;
+        v = (v | (v << 16)) & 0x0000_ffff_0000_ffff;
+        v = (v | (v << 8)) & 0x00ff_00ff_00ff_00ff;
+        v = (v | (v << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+        v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+        (v | (v << 1)) & 0x5555_5555_5555_5555
+    }
+
+    let mut quads: Vec<_> = (1..height)
+        .flat_map(|y| (1..width).map(move |x| (x, y)))
+        .collect();
+    quads.sort_by_key(|&(x, y)| spread(x) | (spread(y) << 1));
+    quads
+}
+
+/// Something that turns a light map into a mesh.
+///
+/// Every generator's `scaling` is the relief depth: the distance from the base of the relief
+/// to its surface over a pure black pixel, in mesh units (millimeters when printed). White
+/// pixels sit on the base. The same value gives the same depth whatever the shape, so the
+/// wall of a cylinder is as thick as the same image printed flat.
+pub trait LithophaneGenerator: Sized {
+    /// Generate the mesh, calling `progress` with the fraction of the work done as it goes.
+    /// The fraction never decreases and the last call is always with 1.0. Errors are found
+    /// before any work is done, so `progress` is never called when generation fails.
+    fn generate_with_progress(
+        self,
+        source: LightMap,
+        progress: &mut dyn FnMut(f32),
+    ) -> Result<Mesh, GenError>;
+
+    fn generate(self, source: LightMap) -> Result<Mesh, GenError> {
+        self.generate_with_progress(source, &mut |_| {})
+    }
+}
+
+/// Errors that stop a generator from building a mesh
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenError {
+    /// The light map has no pixels
+    EmptyLightMap,
+    /// A size setting of the generator is zero or negative, so the mesh would be flat. Holds
+    /// the name of the setting.
+    EmptyDimensions(&'static str),
+    /// The light map has a different number of lightnesses than its dimensions call for
+    DimsMismatch { expected: usize, found: usize },
+}
+
+impl std::fmt::Display for GenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenError::EmptyLightMap => write!(f, "the light map has no pixels"),
+            GenError::EmptyDimensions(setting) => write!(f, "the {setting} must be positive"),
+            GenError::DimsMismatch { expected, found } => write!(
+                f,
+                "the light map should have {expected} lightnesses but has {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenError {}
+
+/// Which way round a lithophane should be, used to give every output of a batch the same
+/// orientation
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    /// Taller than it is wide
+    Portrait,
+    /// Wider than it is tall
+    Landscape,
+}
+
+/// How an overlay's lightness is combined with the lightness underneath it
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendMode {
+    /// Add the overlay to the base, clamped to 1.0
+    Add,
+    /// Subtract the overlay from the base, clamped to 0.0
+    Subtract,
+    /// Use the overlay in place of the base
+    Replace,
+    /// Keep the lighter of the two
+    Max,
+    /// Keep the darker of the two
+    Min,
+}
+
+impl BlendMode {
+    /// Combine a base lightness with an overlay lightness
+    pub fn blend(self, base: f32, overlay: f32) -> f32 {
+        match self {
+            BlendMode::Add => (base + overlay).min(1.0),
+            BlendMode::Subtract => (base - overlay).max(0.0),
+            BlendMode::Replace => overlay,
+            BlendMode::Max => base.max(overlay),
+            BlendMode::Min => base.min(overlay),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct LightMap {
     lightnesses: Vec<f32>,
     dims: (usize, usize),
 }
 
+impl LightMap {
+    /// Check that the map has pixels to generate a mesh from, and one lightness for each
+    pub(crate) fn validate(&self) -> Result<(), GenError> {
+        if self.dims.0 == 0 || self.dims.1 == 0 {
+            return Err(GenError::EmptyLightMap);
+        }
+        let expected = self.dims.0 * self.dims.1;
+        if self.lightnesses.len() != expected {
+            return Err(GenError::DimsMismatch {
+                expected,
+                found: self.lightnesses.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Build a map from row-major lightnesses, where `dims` is (width, height). Lightness runs
+    /// from 0.0 (black, full relief) to 1.0 (white, no relief). Generators reject a map whose
+    /// lightnesses don't fill `dims` exactly.
+    pub fn new(lightnesses: Vec<f32>, dims: (usize, usize)) -> LightMap {
+        LightMap { lightnesses, dims }
+    }
+
+    /// Build a `width` x `height` map by sampling `f(x, y)` at every pixel, with (0, 0) in the
+    /// top-left corner. Useful for generative patterns and test inputs.
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> f32) -> LightMap {
+        let lightnesses = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        LightMap {
+            lightnesses,
+            dims: (width, height),
+        }
+    }
+
+    /// Get the (width, height) of the map
+    pub fn dims(&self) -> (usize, usize) {
+        self.dims
+    }
+
+    /// Get the lightness of every pixel, row by row from the top left
+    pub fn lightnesses(&self) -> &[f32] {
+        &self.lightnesses
+    }
+
+    /// Get the lightness at a fractional position, interpolating bilinearly between the four
+    /// nearest pixels. Pixel centers are at whole coordinates, and positions outside the map
+    /// are clamped to its edge. An empty map, or one with fewer lightnesses than its
+    /// dimensions call for, samples as 0.0 everywhere.
+    pub fn sample_bilinear(&self, x: f32, y: f32) -> f32 {
+        let (width, height) = self.dims;
+        if width == 0 || height == 0 || self.lightnesses.len() < width * height {
+            return 0.0;
+        }
+        let x = x.clamp(0.0, (width - 1) as f32);
+        let y = y.clamp(0.0, (height - 1) as f32);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+        let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+        let at = |x: usize, y: usize| self.lightnesses[y * width + x];
+        let top = at(x0, y0) * (1.0 - tx) + at(x1, y0) * tx;
+        let bottom = at(x0, y1) * (1.0 - tx) + at(x1, y1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+
+    /// Scale the map up by `factor` in both directions with bilinear interpolation, for
+    /// rendering previews finer than the map itself
+    pub fn upscale(&self, factor: usize) -> LightMap {
+        let factor = factor.max(1);
+        let (width, height) = self.dims;
+        let f = factor as f32;
+        // Map the center of each new pixel back onto the original grid
+        LightMap::from_fn(width * factor, height * factor, |x, y| {
+            self.sample_bilinear((x as f32 + 0.5) / f - 0.5, (y as f32 + 0.5) / f - 0.5)
+        })
+    }
+
+    /// Count the lightness values falling into each of `bins` equal-width bins over [0, 1].
+    /// Values outside that range are counted in the first or last bin.
+    pub fn histogram(&self, bins: usize) -> Vec<u32> {
+        let mut counts = vec![0; bins];
+        if bins == 0 {
+            return counts;
+        }
+        for &l in &self.lightnesses {
+            counts[histogram_bin(l, bins)] += 1;
+        }
+        counts
+    }
+
+    /// Repeat the map `count` times side by side
+    pub fn tile_horizontal(&self, count: usize) -> LightMap {
+        let (width, height) = self.dims;
+        if width == 0 {
+            return self.clone();
+        }
+        let lightnesses = self
+            .lightnesses
+            .chunks_exact(width)
+            .flat_map(|row| row.iter().cycle().take(width * count))
+            .copied()
+            .collect();
+        LightMap {
+            lightnesses,
+            dims: (width * count, height),
+        }
+    }
+
+    /// Surround the map with a margin of constant `lightness`. `pad` is the number of pixels to
+    /// add on the (left, top, right, bottom) sides.
+    pub fn pad(&self, pad: (usize, usize, usize, usize), lightness: f32) -> LightMap {
+        let (width, height) = self.dims;
+        let (left, top, right, bottom) = pad;
+        let new_width = left + width + right;
+        let mut lightnesses = vec![lightness; new_width * (top + height + bottom)];
+        // A map with no columns has no rows to copy, only the margin
+        if width > 0 {
+            for (y, row) in self.lightnesses.chunks_exact(width).enumerate() {
+                let start = (y + top) * new_width + left;
+                lightnesses[start..start + width].copy_from_slice(row);
+            }
+        }
+        LightMap {
+            lightnesses,
+            dims: (new_width, top + height + bottom),
+        }
+    }
+
+    /// Fade the last `columns` columns of each row towards its first column, so the right edge
+    /// flows back into the left edge when the map is wrapped or tiled.
+    pub fn blend_seam(&mut self, columns: usize) {
+        let width = self.dims.0;
+        if width == 0 {
+            return;
+        }
+        let columns = columns.min(width - 1);
+        for row in self.lightnesses.chunks_exact_mut(width) {
+            let first = row[0];
+            for i in 0..columns {
+                let t = (i + 1) as f32 / (columns + 1) as f32;
+                let l = &mut row[width - columns + i];
+                *l = *l * (1.0 - t) + first * t;
+            }
+        }
+    }
+
+    /// Rotate the map a quarter turn clockwise if it doesn't already have `orientation`.
+    /// Square maps count as either and are left alone. Fails if the map is empty or its
+    /// lightnesses don't match its dimensions.
+    pub fn orient(&self, orientation: Orientation) -> Result<LightMap, GenError> {
+        self.validate()?;
+        let (width, height) = self.dims;
+        let fits = match orientation {
+            Orientation::Portrait => height >= width,
+            Orientation::Landscape => width >= height,
+        };
+        if fits {
+            return Ok(LightMap {
+                lightnesses: self.lightnesses.clone(),
+                dims: self.dims,
+            });
+        }
+        // The new row y is the old column y read from the bottom up
+        Ok(LightMap::from_fn(height, width, |x, y| {
+            self.lightnesses[(height - 1 - x) * width + y]
+        }))
+    }
+
+    /// Map every lightness through `profile`
+    pub fn apply_profile(&mut self, profile: ReliefProfile) {
+        if profile != ReliefProfile::Continuous {
+            self.lightnesses
+                .iter_mut()
+                .for_each(|l| *l = profile.apply(*l));
+        }
+    }
+
+    /// Snap every lightness in `low..=high` to `level`, so that region of the relief comes out
+    /// perfectly flat. Useful for giving logos and text a clean flat top.
+    pub fn flatten_range(&mut self, low: f32, high: f32, level: f32) {
+        self.lightnesses
+            .iter_mut()
+            .filter(|l| (low..=high).contains(*l))
+            .for_each(|l| *l = level);
+    }
+
+    /// Stretch `floor..=ceiling` to the full lightness range, like the black and white points
+    /// of a levels tool. Lightness below the floor is clamped to black and above the ceiling to
+    /// white, losing the detail there; [`preview::clipping`] marks those pixels.
+    pub fn levels(&mut self, floor: f32, ceiling: f32) {
+        if (floor, ceiling) == (0.0, 1.0) {
+            return;
+        }
+        let span = (ceiling - floor).max(f32::EPSILON);
+        self.lightnesses
+            .iter_mut()
+            .for_each(|l| *l = ((*l - floor) / span).clamp(0.0, 1.0));
+    }
+
+    /// Composite `overlay` onto this map with its top-left corner at `offset`, combining the
+    /// overlapping pixels with `mode`. Parts of the overlay outside the map are ignored. Fails
+    /// if either map is empty or its lightnesses don't match its dimensions.
+    pub fn composite(
+        &mut self,
+        overlay: &LightMap,
+        offset: (usize, usize),
+        mode: BlendMode,
+    ) -> Result<(), GenError> {
+        self.validate()?;
+        overlay.validate()?;
+        let (width, height) = self.dims;
+        let (ox, oy) = offset;
+        for y in 0..overlay.dims.1.min(height.saturating_sub(oy)) {
+            for x in 0..overlay.dims.0.min(width.saturating_sub(ox)) {
+                let base = &mut self.lightnesses[(y + oy) * width + x + ox];
+                *base = mode.blend(*base, overlay.lightnesses[y * overlay.dims.0 + x]);
+            }
+        }
+        Ok(())
+    }
+}
+
 pub trait ImagePreprocessor {
     fn transform(self, image: &DynamicImage) -> LightMap;
 }
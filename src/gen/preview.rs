@@ -0,0 +1,116 @@
+use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
+
+use super::LightMap;
+
+/// Get the (dz/dx, dz/dy) gradient of the heightmap at (x, y) using central differences
+fn gradient(heights: &[f32], dims: (usize, usize), x: usize, y: usize) -> (f32, f32) {
+    let (width, height) = dims;
+    let at = |x: usize, y: usize| heights[y * width + x];
+
+    let (l, r) = (x.saturating_sub(1), (x + 1).min(width - 1));
+    let (t, b) = (y.saturating_sub(1), (y + 1).min(height - 1));
+    let dx = if r > l {
+        (at(r, y) - at(l, y)) / (r - l) as f32
+    } else {
+        0.0
+    };
+    let dy = if b > t {
+        (at(x, b) - at(x, t)) / (b - t) as f32
+    } else {
+        0.0
+    };
+    (dx, dy)
+}
+
+/// Render a hillshade of the heightmap a generator would build from `map`.
+///
+/// Heights are computed the same way as the mesh generators do, with `scaling` as the
+/// multiplier. The light comes from `azimuth` degrees clockwise from the top of the image
+/// and `elevation` degrees above the horizon.
+pub fn hillshade(map: &LightMap, scaling: f32, azimuth: f32, elevation: f32) -> DynamicImage {
+    let (width, height) = map.dims;
+    // Dark pixels stick out of the lithophane, so they are the tallest points
+    let heights: Vec<_> = map.lightnesses.iter().map(|l| -l * scaling).collect();
+
+    let (az_sin, az_cos) = azimuth.to_radians().sin_cos();
+    let (el_sin, el_cos) = elevation.to_radians().sin_cos();
+    let light = (az_sin * el_cos, -az_cos * el_cos, el_sin);
+
+    let mut image = GrayImage::new(width as u32, height as u32);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = gradient(&heights, map.dims, x, y);
+            let len = (dx * dx + dy * dy + 1.0).sqrt();
+            let shade = (-dx * light.0 - dy * light.1 + light.2) / len;
+            let value = (shade.clamp(0.0, 1.0) * 255.0).round() as u8;
+            image.put_pixel(x as u32, y as u32, Luma([value]));
+        }
+    }
+
+    DynamicImage::ImageLuma8(image)
+}
+
+/// Render a hillshade like `hillshade`, but `factor` times larger in each direction. The map
+/// is upscaled with bilinear interpolation first, and the relief is scaled to match so the
+/// shading looks the same as at the original size, only smoother.
+pub fn hillshade_supersampled(
+    map: &LightMap,
+    scaling: f32,
+    azimuth: f32,
+    elevation: f32,
+    factor: usize,
+) -> DynamicImage {
+    let factor = factor.max(1);
+    hillshade(
+        &map.upscale(factor),
+        scaling * factor as f32,
+        azimuth,
+        elevation,
+    )
+}
+
+/// Whether a lightness value was clipped by the floor or ceiling
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Clip {
+    None,
+    Floor,
+    Ceiling,
+}
+
+/// Mark which pixels are below `floor` or above `ceiling`, the ones `LightMap::levels` with
+/// the same floor and ceiling clamps to black or white. Pixels exactly at either keep their
+/// place in the range, so the full range of 0.0 to 1.0 marks nothing.
+pub fn clipping(map: &LightMap, floor: f32, ceiling: f32) -> Vec<Clip> {
+    map.lightnesses
+        .iter()
+        .map(|&l| {
+            if l < floor {
+                Clip::Floor
+            } else if l > ceiling {
+                Clip::Ceiling
+            } else {
+                Clip::None
+            }
+        })
+        .collect()
+}
+
+/// Render the map as a grayscale heightmap, painting pixels clipped at the floor blue and
+/// pixels clipped at the ceiling red. Pass a map from `LightMap::upscale` for a larger render.
+pub fn heightmap(map: &LightMap, floor: f32, ceiling: f32) -> DynamicImage {
+    let (width, height) = map.dims;
+    let clips = clipping(map, floor, ceiling);
+    let image = RgbImage::from_fn(width as u32, height as u32, |x, y| {
+        let i = y as usize * width + x as usize;
+        match clips[i] {
+            Clip::Floor => Rgb([0, 64, 255]),
+            Clip::Ceiling => Rgb([255, 32, 0]),
+            Clip::None => {
+                let v = (map.lightnesses[i].clamp(0.0, 1.0) * 255.0).round() as u8;
+                Rgb([v, v, v])
+            }
+        }
+    });
+
+    DynamicImage::ImageRgb8(image)
+}
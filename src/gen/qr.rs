@@ -0,0 +1,71 @@
+use qrcode::{Color, QrCode};
+
+use crate::error::LithError;
+
+use super::{BlendMode, LightMap};
+
+/// Width of the light border a QR code needs around it to be found by scanners, in modules
+const QUIET_ZONE: usize = 4;
+/// Lightness of the light modules. Pure white would leave no material at all, so they are
+/// kept as a thin layer that still lets plenty of light through.
+const LIGHT_MODULE: f32 = 0.85;
+
+/// A corner of the light map
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Rasterize `text` as a QR code with each module `module` pixels wide, including the quiet
+/// zone. Dark modules get the full relief depth and light modules a thin layer.
+pub fn qr_light_map(text: &str, module: usize) -> Result<LightMap, LithError> {
+    let code = QrCode::new(text)
+        .map_err(|_| LithError::InvalidOptions("text can't be encoded as a QR code"))?;
+    let module = module.max(1);
+    let colors = code.to_colors();
+    let modules = code.width();
+    let size = (modules + 2 * QUIET_ZONE) * module;
+
+    Ok(LightMap::from_fn(size, size, |x, y| {
+        let (mx, my) = (x / module, y / module);
+        let inside = QUIET_ZONE..QUIET_ZONE + modules;
+        if inside.contains(&mx) && inside.contains(&my) {
+            match colors[(my - QUIET_ZONE) * modules + mx - QUIET_ZONE] {
+                Color::Dark => 0.0,
+                Color::Light => LIGHT_MODULE,
+            }
+        } else {
+            LIGHT_MODULE
+        }
+    }))
+}
+
+impl LightMap {
+    /// Replace the given corner of the map with a QR code encoding `text`.
+    ///
+    /// Each module is `module` pixels wide. To scan reliably once printed, a module should
+    /// come out at least 1mm across, so pick it together with the pixel size. Fails if the
+    /// text can't be encoded or the code doesn't fit in the map.
+    pub fn embed_qr(&mut self, text: &str, corner: Corner, module: usize) -> Result<(), LithError> {
+        let qr = qr_light_map(text, module)?;
+        let (width, height) = self.dims;
+        let size = qr.dims.0;
+        if size > width || size > height {
+            return Err(LithError::InvalidOptions(
+                "QR code doesn't fit in the image",
+            ));
+        }
+
+        let offset = match corner {
+            Corner::TopLeft => (0, 0),
+            Corner::TopRight => (width - size, 0),
+            Corner::BottomLeft => (0, height - size),
+            Corner::BottomRight => (width - size, height - size),
+        };
+        self.composite(&qr, offset, BlendMode::Replace)?;
+        Ok(())
+    }
+}
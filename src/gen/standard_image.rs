@@ -1,10 +1,14 @@
-use crate::img::{luminance_to_lightness, srgb_to_luminance};
+use crate::img::{LightnessConversion, LuminanceWeights};
 
-use super::{ImagePreprocessor, LightMap};
+use super::{fitted_dims, resize_bounds, ImagePreprocessor, LightMap};
 
-#[derive(Default)]
 pub struct StandardImagePreprocessor {
     width: usize,
+    height: usize,
+    conversion: LightnessConversion,
+    gamma: f32,
+    contrast: f32,
+    brightness: f32,
 }
 
 impl StandardImagePreprocessor {
@@ -12,24 +16,127 @@ impl StandardImagePreprocessor {
         self.width = width;
         self
     }
+
+    /// Resize the image to at most `height` pixels tall, keeping its aspect ratio. With a
+    /// width set as well, the image is fit within both. With neither set, the image keeps its
+    /// own size.
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Get the (width, height) of the light map produced for an image of the given size
+    pub fn output_dims(&self, width: u32, height: u32) -> (usize, usize) {
+        fitted_dims(width, height, (self.width, self.height))
+    }
+
+    /// Set the gamma applied to each pixel in linear light, before the luminance is computed.
+    /// See [`LightnessConversion`] for the full order of operations.
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.conversion = self.conversion.linear_gamma(gamma);
+        self
+    }
+
+    /// Multiply the relief of each pixel by its opacity, so transparent areas are carved
+    /// thinner. See [`LightnessConversion`] for the exact formula.
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.alpha_relief(enabled);
+        self
+    }
+
+    /// Map luminance to perceived lightness before it becomes relief, which is on by default.
+    /// See [`LightnessConversion::perceptual`].
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.perceptual(enabled);
+        self
+    }
+
+    /// Swap light and dark so bright areas get the thick relief, for front-lit pieces. See
+    /// [`LightnessConversion::invert`].
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.invert(enabled);
+        self
+    }
+
+    /// Drop pixels more transparent than `threshold` to the base. See
+    /// [`LightnessConversion::alpha_threshold`].
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.conversion = self.conversion.alpha_threshold(threshold);
+        self
+    }
+
+    /// Set the lightness of pixels below the alpha threshold. See
+    /// [`LightnessConversion::transparent_lightness`].
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.conversion = self.conversion.transparent_lightness(lightness);
+        self
+    }
+
+    /// Set the weights used to sum each pixel's channels into luminance. See
+    /// [`LuminanceWeights`].
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.conversion = self.conversion.weights(weights);
+        self
+    }
+
+    /// Raise each lightness to `gamma`. This is the first of the tone adjustments, which run
+    /// in the order gamma, contrast, brightness on the final lightness and then clamp it to
+    /// \[0, 1].
+    pub fn gamma(mut self, gamma: f32) -> Self {
+        self.gamma = gamma;
+        self
+    }
+
+    /// Stretch each lightness away from mid gray by `contrast`, after the gamma. Values above
+    /// 1.0 add contrast and values below 1.0 take it away.
+    pub fn contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast;
+        self
+    }
+
+    /// Add `brightness` to each lightness, after the gamma and contrast. Positive values
+    /// lighten the image and so thin the relief.
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    /// Apply the tone adjustments to a single lightness. With neutral settings the lightness
+    /// is left exactly as it was.
+    fn adjust_tone(&self, l: f32) -> f32 {
+        if self.gamma == 1.0 && self.contrast == 1.0 && self.brightness == 0.0 {
+            return l;
+        }
+        let l = l.max(0.0).powf(self.gamma);
+        let l = (l - 0.5) * self.contrast + 0.5;
+        (l + self.brightness).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for StandardImagePreprocessor {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            conversion: LightnessConversion::default(),
+            gamma: 1.0,
+            contrast: 1.0,
+            brightness: 0.0,
+        }
+    }
 }
 
 impl ImagePreprocessor for StandardImagePreprocessor {
     fn transform(self, image: &image::DynamicImage) -> LightMap {
-        let image = image.resize(
-            self.width as u32,
-            image.height(),
-            image::imageops::FilterType::CatmullRom,
-        );
-        let lights: Vec<_> = image
-            .to_rgb8()
-            .chunks_exact(3)
-            .map(srgb_to_luminance)
-            .map(luminance_to_lightness)
-            .map(|l| l / 100.0)
-            .collect();
+        let (width, height) =
+            resize_bounds(image.width(), image.height(), (self.width, self.height));
+        let image = image.resize(width, height, image::imageops::FilterType::CatmullRom);
+        let lightnesses = self.conversion.lightnesses(&image);
         LightMap {
-            lightnesses: lights,
+            lightnesses: lightnesses
+                .into_iter()
+                .map(|l| self.adjust_tone(l))
+                .collect(),
             dims: (image.width() as usize, image.height() as usize),
         }
     }
@@ -0,0 +1,257 @@
+use std::{collections::BTreeMap, fs::File, io::BufReader, path::Path};
+
+use crate::{
+    error::LithError,
+    img::{LightnessConversion, LuminanceWeights},
+};
+
+use super::{resized_dims, ImagePreprocessor, LightMap};
+
+/// Preprocessor for images too large to hold in memory, which reads the source one row at a
+/// time and averages each block of rows into a row of the light map as it goes.
+///
+/// Each output pixel is the area-weighted average of the source pixels it covers, so the
+/// result doesn't depend on how the rows are fed in. PNG files are streamed straight from disk
+/// by [`TiledImagePreprocessor::transform_file`]; other formats (and interlaced PNGs) are
+/// decoded in full first, since their decoders can't produce rows incrementally.
+#[derive(Default)]
+pub struct TiledImagePreprocessor {
+    width: usize,
+    conversion: LightnessConversion,
+}
+
+impl TiledImagePreprocessor {
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the gamma applied to each pixel in linear light, before the luminance is computed.
+    /// See [`LightnessConversion`] for the full order of operations.
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.conversion = self.conversion.linear_gamma(gamma);
+        self
+    }
+
+    /// Multiply the relief of each pixel by its opacity, so transparent areas are carved
+    /// thinner. See [`LightnessConversion`] for the exact formula.
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.alpha_relief(enabled);
+        self
+    }
+
+    /// Map luminance to perceived lightness before it becomes relief, which is on by default.
+    /// See [`LightnessConversion::perceptual`].
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.perceptual(enabled);
+        self
+    }
+
+    /// Swap light and dark so bright areas get the thick relief, for front-lit pieces. See
+    /// [`LightnessConversion::invert`].
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.conversion = self.conversion.invert(enabled);
+        self
+    }
+
+    /// Drop pixels more transparent than `threshold` to the base. See
+    /// [`LightnessConversion::alpha_threshold`].
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.conversion = self.conversion.alpha_threshold(threshold);
+        self
+    }
+
+    /// Set the lightness of pixels below the alpha threshold. See
+    /// [`LightnessConversion::transparent_lightness`].
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.conversion = self.conversion.transparent_lightness(lightness);
+        self
+    }
+
+    /// Set the weights used to sum each pixel's channels into luminance. See
+    /// [`LuminanceWeights`].
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.conversion = self.conversion.weights(weights);
+        self
+    }
+
+    /// Get the (width, height) of the light map produced for an image of the given size
+    pub fn output_dims(&self, width: u32, height: u32) -> (usize, usize) {
+        resized_dims(width, height, self.width)
+    }
+
+    /// Build the light map for the image at `path`, streaming it row by row when possible
+    pub fn transform_file<P: AsRef<Path>>(self, path: P) -> Result<LightMap, LithError> {
+        let path = path.as_ref();
+        let is_png = image::ImageFormat::from_path(path).ok() == Some(image::ImageFormat::Png);
+        if !is_png {
+            return Ok(self.transform(&crate::img::load_image(path)?));
+        }
+
+        let corrupt = |e: png::DecodingError| match e {
+            png::DecodingError::IoError(e) => LithError::from(image::ImageError::IoError(e)),
+            e => LithError::CorruptImage(e.to_string()),
+        };
+        let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+        // Expand palettes and low bit depths, but keep 16-bit samples
+        decoder.set_transformations(png::Transformations::EXPAND);
+        let mut reader = decoder.read_info().map_err(corrupt)?;
+        // Interlaced images come out in passes rather than rows
+        if reader.info().interlaced {
+            return Ok(self.transform(&crate::img::load_image(path)?));
+        }
+
+        let (width, height) = (reader.info().width, reader.info().height);
+        let (color, depth) = reader.output_color_type();
+        let deep = depth == png::BitDepth::Sixteen;
+        let sample_bytes = if deep { 2 } else { 1 };
+        let channels = color.samples();
+        let mut rows = Vec::with_capacity(height as usize);
+        let mut downsampler = Downsampler::new(width, height, self.width);
+        while let Some(row) = reader.next_row().map_err(corrupt)? {
+            // 16-bit samples are big-endian
+            let samples: Vec<f32> = if deep {
+                row.data()
+                    .chunks_exact(sample_bytes)
+                    .map(|s| u16::from_be_bytes([s[0], s[1]]) as f32 / 65535.0)
+                    .collect()
+            } else {
+                row.data().iter().map(|&s| s as f32 / 255.0).collect()
+            };
+            let rgba: Vec<[f32; 4]> = samples
+                .chunks_exact(channels)
+                .map(|p| match color {
+                    png::ColorType::Grayscale => [p[0], p[0], p[0], 1.0],
+                    png::ColorType::GrayscaleAlpha => [p[0], p[0], p[0], p[1]],
+                    png::ColorType::Rgb => [p[0], p[1], p[2], 1.0],
+                    _ => [p[0], p[1], p[2], p[3]],
+                })
+                .collect();
+            downsampler.push_row(&rgba, |row| rows.push(self.convert(row)));
+        }
+        Ok(downsampler.finish(rows))
+    }
+
+    fn convert(&self, row: Vec<[f32; 4]>) -> Vec<f32> {
+        row.into_iter()
+            .map(|p| self.conversion.lightness_rgba_normalized(p))
+            .collect()
+    }
+}
+
+impl ImagePreprocessor for TiledImagePreprocessor {
+    fn transform(self, image: &image::DynamicImage) -> LightMap {
+        // Go through 16 bits so deep images keep their precision
+        let image = image.to_rgba16();
+        let (width, height) = image.dimensions();
+        let mut rows = Vec::with_capacity(height as usize);
+        let mut downsampler = Downsampler::new(width, height, self.width);
+        for row in image.rows() {
+            let rgba: Vec<[f32; 4]> = row.map(|p| p.0.map(|c| c as f32 / 65535.0)).collect();
+            downsampler.push_row(&rgba, |row| rows.push(self.convert(row)));
+        }
+        downsampler.finish(rows)
+    }
+}
+
+/// Box-filter downsampler that takes source rows of normalized RGBA one at a time
+struct Downsampler {
+    dims: (usize, usize),
+    /// Source rows per output row
+    scale_y: f64,
+    /// For each source column, the output columns it overlaps and by how much
+    columns: Vec<Vec<(usize, f64)>>,
+    /// Area of source pixels covered by one output pixel
+    area: f64,
+    /// Partially accumulated output rows, keyed by row index
+    open: BTreeMap<usize, Vec<[f64; 4]>>,
+    next_row: usize,
+}
+
+/// Get the length of the overlap between the intervals [a0, a1) and [b0, b1)
+fn overlap(a0: f64, a1: f64, b0: f64, b1: f64) -> f64 {
+    (a1.min(b1) - a0.max(b0)).max(0.0)
+}
+
+impl Downsampler {
+    fn new(width: u32, height: u32, target_width: usize) -> Self {
+        let dims = resized_dims(width, height, target_width);
+        let scale_x = width as f64 / dims.0.max(1) as f64;
+        let scale_y = height as f64 / dims.1.max(1) as f64;
+        let columns = (0..width as usize)
+            .map(|x| {
+                let (x0, x1) = (x as f64, x as f64 + 1.0);
+                let first = (x0 / scale_x).floor() as usize;
+                (first..dims.0)
+                    .map(|i| {
+                        (
+                            i,
+                            overlap(x0, x1, i as f64 * scale_x, (i + 1) as f64 * scale_x),
+                        )
+                    })
+                    .take_while(|&(_, w)| w > 0.0)
+                    .collect()
+            })
+            .collect();
+        Self {
+            dims,
+            scale_y,
+            columns,
+            area: scale_x * scale_y,
+            open: BTreeMap::new(),
+            next_row: 0,
+        }
+    }
+
+    /// Add the next source row, calling `emit` with every output row it completes
+    fn push_row(&mut self, row: &[[f32; 4]], mut emit: impl FnMut(Vec<[f32; 4]>)) {
+        let y = self.next_row;
+        self.next_row += 1;
+        let (y0, y1) = (y as f64, y as f64 + 1.0);
+        let first = (y0 / self.scale_y).floor() as usize;
+
+        for j in first..self.dims.1 {
+            let wy = overlap(
+                y0,
+                y1,
+                j as f64 * self.scale_y,
+                (j + 1) as f64 * self.scale_y,
+            );
+            if wy <= 0.0 {
+                break;
+            }
+            let acc = self
+                .open
+                .entry(j)
+                .or_insert_with(|| vec![[0.0; 4]; self.dims.0]);
+            for (pixel, columns) in row.iter().zip(&self.columns) {
+                for &(i, wx) in columns {
+                    for c in 0..4 {
+                        acc[i][c] += pixel[c] as f64 * wx * wy;
+                    }
+                }
+            }
+        }
+
+        // Rows whose whole area has been seen are done
+        while let Some(entry) = self.open.first_entry() {
+            if (*entry.key() + 1) as f64 * self.scale_y > y1 + 1e-9 {
+                break;
+            }
+            let area = self.area;
+            let row = entry.remove();
+            emit(
+                row.iter()
+                    .map(|p| p.map(|c| (c / area).clamp(0.0, 1.0) as f32))
+                    .collect(),
+            );
+        }
+    }
+
+    fn finish(self, rows: Vec<Vec<f32>>) -> LightMap {
+        LightMap {
+            lightnesses: rows.into_iter().flatten().collect(),
+            dims: self.dims,
+        }
+    }
+}
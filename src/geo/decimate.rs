@@ -0,0 +1,369 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::{vertex_key, Mesh, Vec3};
+
+/// Weight of the planes that pin open boundaries in place, relative to the surface planes
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+/// Smallest cosine allowed between a triangle's normal before and after a collapse
+const MIN_NORMAL_COS: f64 = 0.2;
+
+type Point = [f64; 3];
+
+fn sub(a: &Point, b: &Point) -> Point {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: &Point, b: &Point) -> Point {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn dot(a: &Point, b: &Point) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(a: &Point) -> f64 {
+    dot(a, a).sqrt()
+}
+
+/// Symmetric 4x4 error quadric, stored as its upper triangle
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    /// The squared distance to the plane through `p` with unit normal `n`, scaled by `weight`
+    fn plane(n: &Point, p: &Point, weight: f64) -> Self {
+        let [a, b, c] = *n;
+        let d = -dot(n, p);
+        Quadric(
+            [
+                a * a,
+                a * b,
+                a * c,
+                a * d,
+                b * b,
+                b * c,
+                b * d,
+                c * c,
+                c * d,
+                d * d,
+            ]
+            .map(|v| v * weight),
+        )
+    }
+
+    fn add(&mut self, other: &Quadric) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a += b;
+        }
+    }
+
+    fn error(&self, p: &Point) -> f64 {
+        let q = &self.0;
+        let [x, y, z] = *p;
+        q[0] * x * x
+            + 2.0 * q[1] * x * y
+            + 2.0 * q[2] * x * z
+            + 2.0 * q[3] * x
+            + q[4] * y * y
+            + 2.0 * q[5] * y * z
+            + 2.0 * q[6] * y
+            + q[7] * z * z
+            + 2.0 * q[8] * z
+            + q[9]
+    }
+
+    /// Find the point with the smallest error, if the quadric isn't degenerate
+    fn minimum(&self) -> Option<Point> {
+        let q = &self.0;
+        let m = [[q[0], q[1], q[2]], [q[1], q[4], q[5]], [q[2], q[5], q[7]]];
+        let b = [-q[3], -q[6], -q[8]];
+        let det = dot(&m[0], &cross(&m[1], &m[2]));
+        let scale = m.iter().flatten().fold(0.0f64, |s, v| s.max(v.abs()));
+        if det.abs() <= 1e-9 * scale.powi(3) {
+            return None;
+        }
+        // Cramer's rule
+        let column = |i: usize| {
+            let mut m = m;
+            for (row, b) in m.iter_mut().zip(b) {
+                row[i] = b;
+            }
+            dot(&m[0], &cross(&m[1], &m[2])) / det
+        };
+        Some([column(0), column(1), column(2)])
+    }
+}
+
+/// A possible edge collapse, valid as long as neither vertex has changed since it was queued
+struct Candidate {
+    cost: f64,
+    u: usize,
+    v: usize,
+    stamps: (u32, u32),
+    target: Point,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost.total_cmp(&other.cost) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the cheapest collapse is at the top of the max-heap
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// An indexed copy of a mesh that edges can be collapsed in
+struct Decimator {
+    points: Vec<Point>,
+    faces: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    vertex_faces: Vec<Vec<usize>>,
+    quadrics: Vec<Quadric>,
+    stamps: Vec<u32>,
+}
+
+impl Decimator {
+    fn new(mesh: &Mesh) -> Self {
+        let mut index = HashMap::new();
+        let mut points = vec![];
+        let mut faces = vec![];
+        for t in mesh.vertices.chunks_exact(3) {
+            let face = [0, 1, 2].map(|i| {
+                *index.entry(vertex_key(&t[i])).or_insert_with(|| {
+                    points.push([t[i].x as f64, t[i].y as f64, t[i].z as f64]);
+                    points.len() - 1
+                })
+            });
+            if face[0] != face[1] && face[1] != face[2] && face[2] != face[0] {
+                faces.push(face);
+            }
+        }
+
+        let mut vertex_faces = vec![vec![]; points.len()];
+        let mut quadrics = vec![Quadric::default(); points.len()];
+        let mut edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for (f, face) in faces.iter().enumerate() {
+            let [a, b, c] = face.map(|i| points[i]);
+            let n = cross(&sub(&b, &a), &sub(&c, &a));
+            let area = length(&n);
+            if area > 0.0 {
+                let unit = n.map(|v| v / area);
+                let q = Quadric::plane(&unit, &a, area / 2.0);
+                face.iter().for_each(|&i| quadrics[i].add(&q));
+            }
+            for i in 0..3 {
+                vertex_faces[face[i]].push(f);
+                let (a, b) = (face[i], face[(i + 1) % 3]);
+                edges.entry((a.min(b), a.max(b))).or_insert((0, f)).0 += 1;
+            }
+        }
+
+        // Pin open boundaries with planes standing perpendicular to the surface along them
+        for (&(a, b), &(uses, f)) in &edges {
+            if uses != 1 {
+                continue;
+            }
+            let [p, q, r] = faces[f].map(|i| points[i]);
+            let normal = cross(&sub(&q, &p), &sub(&r, &p));
+            let edge = sub(&points[b], &points[a]);
+            let n = cross(&edge, &normal);
+            let len = length(&n);
+            if len > 0.0 {
+                let q = Quadric::plane(
+                    &n.map(|v| v / len),
+                    &points[a],
+                    BOUNDARY_WEIGHT * dot(&edge, &edge),
+                );
+                quadrics[a].add(&q);
+                quadrics[b].add(&q);
+            }
+        }
+
+        Self {
+            stamps: vec![0; points.len()],
+            alive: vec![true; faces.len()],
+            points,
+            faces,
+            vertex_faces,
+            quadrics,
+        }
+    }
+
+    fn live_faces(&self, v: usize) -> impl Iterator<Item = usize> + '_ {
+        self.vertex_faces[v]
+            .iter()
+            .copied()
+            .filter(|&f| self.alive[f])
+    }
+
+    fn neighbors(&self, v: usize) -> Vec<usize> {
+        let mut neighbors: Vec<usize> = self
+            .live_faces(v)
+            .flat_map(|f| self.faces[f])
+            .filter(|&w| w != v)
+            .collect();
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+    }
+
+    fn candidate(&self, u: usize, v: usize) -> Candidate {
+        let mut q = self.quadrics[u];
+        q.add(&self.quadrics[v]);
+        let (a, b) = (self.points[u], self.points[v]);
+        let mid = [0, 1, 2].map(|i| (a[i] + b[i]) / 2.0);
+        let (cost, target) = q
+            .minimum()
+            // Ignore solutions that wander far from the edge, which happen on nearly flat areas
+            .filter(|p| length(&sub(p, &mid)) <= length(&sub(&a, &b)))
+            .into_iter()
+            .chain([a, b, mid])
+            .map(|p| (q.error(&p), p))
+            .min_by(|x, y| x.0.total_cmp(&y.0))
+            .unwrap();
+        Candidate {
+            cost,
+            u,
+            v,
+            stamps: (self.stamps[u], self.stamps[v]),
+            target,
+        }
+    }
+
+    /// Check that collapsing `u` and `v` into `target` keeps the mesh manifold and doesn't flip
+    /// any of the triangles that survive
+    fn can_collapse(&self, u: usize, v: usize, target: &Point) -> bool {
+        let shared_faces = self
+            .live_faces(u)
+            .filter(|&f| self.faces[f].contains(&v))
+            .count();
+        let nu = self.neighbors(u);
+        let shared_neighbors = self
+            .neighbors(v)
+            .iter()
+            .filter(|w| nu.binary_search(w).is_ok())
+            .count();
+        if shared_neighbors != shared_faces {
+            return false;
+        }
+
+        [(u, v), (v, u)].iter().all(|&(moving, other)| {
+            self.live_faces(moving)
+                .filter(|&f| !self.faces[f].contains(&other))
+                .all(|f| {
+                    let face = self.faces[f];
+                    let before = face.map(|i| self.points[i]);
+                    let after = face.map(|i| if i == moving { *target } else { self.points[i] });
+                    let normal = |[a, b, c]: [Point; 3]| cross(&sub(&b, &a), &sub(&c, &a));
+                    let (n0, n1) = (normal(before), normal(after));
+                    let (l0, l1) = (length(&n0), length(&n1));
+                    l1 > 0.0 && (l0 == 0.0 || dot(&n0, &n1) >= MIN_NORMAL_COS * l0 * l1)
+                })
+        })
+    }
+
+    /// Merge `v` into `u` at `target`, returning how many faces were removed
+    fn collapse(&mut self, u: usize, v: usize, target: Point) -> usize {
+        let mut removed = 0;
+        for f in std::mem::take(&mut self.vertex_faces[v]) {
+            if !self.alive[f] {
+                continue;
+            }
+            if self.faces[f].contains(&u) {
+                self.alive[f] = false;
+                removed += 1;
+            } else {
+                self.faces[f].iter_mut().for_each(|i| {
+                    if *i == v {
+                        *i = u;
+                    }
+                });
+                self.vertex_faces[u].push(f);
+            }
+        }
+        self.vertex_faces[u].retain(|&f| self.alive[f]);
+
+        let q = self.quadrics[v];
+        self.quadrics[u].add(&q);
+        self.points[u] = target;
+        self.stamps[u] += 1;
+        self.stamps[v] += 1;
+        removed
+    }
+
+    fn run(&mut self, target: usize) {
+        let mut heap = BinaryHeap::new();
+        for face in &self.faces {
+            for i in 0..3 {
+                let (u, v) = (face[i], face[(i + 1) % 3]);
+                if u < v {
+                    heap.push(self.candidate(u, v));
+                }
+            }
+        }
+
+        let mut remaining = self.faces.len();
+        while remaining > target {
+            let Some(c) = heap.pop() else {
+                break;
+            };
+            if c.stamps != (self.stamps[c.u], self.stamps[c.v]) {
+                continue;
+            }
+            if !self.can_collapse(c.u, c.v, &c.target) {
+                continue;
+            }
+            remaining -= self.collapse(c.u, c.v, c.target);
+            for w in self.neighbors(c.u) {
+                heap.push(self.candidate(c.u, w));
+            }
+        }
+    }
+
+    fn into_mesh(self) -> Mesh {
+        let vertex = |p: Point| Vec3 {
+            x: p[0] as f32,
+            y: p[1] as f32,
+            z: p[2] as f32,
+        };
+        let vertices = self
+            .faces
+            .iter()
+            .zip(&self.alive)
+            .filter(|(_, &alive)| alive)
+            .flat_map(|(face, _)| face.map(|i| vertex(self.points[i])))
+            .collect();
+        Mesh::new(vertices)
+    }
+}
+
+impl Mesh {
+    /// Simplify the mesh to at most `target` triangles by repeatedly collapsing the edge whose
+    /// removal changes the shape the least, measured with quadric error metrics.
+    ///
+    /// Vertices are welded by position first. Collapses that would fold a triangle over or make
+    /// the mesh non-manifold are skipped, and open boundaries are held in place, so the result
+    /// can stay above `target` if no more edges can be removed safely.
+    pub fn decimate_to(&self, target: usize) -> Mesh {
+        let mut decimator = Decimator::new(self);
+        decimator.run(target);
+        decimator.into_mesh()
+    }
+}
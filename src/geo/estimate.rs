@@ -0,0 +1,74 @@
+use super::Mesh;
+
+/// Plastic a typical FDM printer can melt per second, in mm³
+const VOLUMETRIC_FLOW: f32 = 8.0;
+/// Speed of the outer walls, which follow the surface of the model, in mm/s
+const WALL_SPEED: f32 = 40.0;
+/// Time spent per layer on travel moves, retractions and the z change, in seconds
+const LAYER_OVERHEAD: f32 = 2.0;
+
+/// A very rough guess at how long a mesh takes to print, based on typical speeds. Useful for
+/// telling a few hours from a few days, not for planning to the minute.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PrintEstimate {
+    /// Volume of plastic in the part, in mm³
+    pub volume_mm3: f32,
+    /// Area of the outside of the part, in mm²
+    pub surface_area_mm2: f32,
+    /// Number of layers, printing the mesh in its current orientation
+    pub layers: usize,
+    /// Estimated print time in seconds
+    pub seconds: f32,
+}
+
+impl std::fmt::Display for PrintEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let minutes = (self.seconds / 60.0).round() as usize;
+        write!(
+            f,
+            "~{}h {:02}m over {} layers",
+            minutes / 60,
+            minutes % 60,
+            self.layers
+        )
+    }
+}
+
+impl Mesh {
+    /// Get the total area of the triangles in the mesh
+    pub fn surface_area(&self) -> f32 {
+        self.vertices
+            .chunks_exact(3)
+            .map(|t| (&t[1] - &t[0]).cross(&(&t[2] - &t[0])).length() / 2.0)
+            .sum()
+    }
+
+    /// Estimate how long the mesh takes to print with layers `layer_height` mm thick, standing
+    /// as it is with +Z up. The estimate adds the time to extrude the volume at a typical flow
+    /// rate, the time to trace the surface with outer walls, and a fixed overhead per layer.
+    pub fn print_estimate(&self, layer_height: f32) -> PrintEstimate {
+        let volume_mm3 = self.volume().abs();
+        let surface_area_mm2 = self.surface_area();
+        let layers = if layer_height > 0.0 {
+            (self.dimensions().z / layer_height).ceil() as usize
+        } else {
+            0
+        };
+        // Each layer traces the outline of the surface, so the walls add up to the surface
+        // area spread over the layer height
+        let wall_length = if layer_height > 0.0 {
+            surface_area_mm2 / layer_height
+        } else {
+            0.0
+        };
+        let seconds = volume_mm3 / VOLUMETRIC_FLOW
+            + wall_length / WALL_SPEED
+            + layers as f32 * LAYER_OVERHEAD;
+        PrintEstimate {
+            volume_mm3,
+            surface_area_mm2,
+            layers,
+            seconds,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::{collections::HashMap, fmt::Write, hash::Hash};
+
+use super::{vertex_key, Mesh, Vec3};
+
+/// A table of unique vertices and triangles indexing into it
+type Indexed = (Vec<Vec3>, Vec<[usize; 3]>);
+
+impl Mesh {
+    /// Build an indexed copy of the mesh, merging vertices whose `key` is equal. The first
+    /// vertex seen for each key is kept, and triangle order and winding are unchanged.
+    fn index_by<K: Hash + Eq>(&self, key: impl Fn(&Vec3) -> K) -> Indexed {
+        let mut index: HashMap<K, usize> = HashMap::new();
+        let mut vertices = vec![];
+        let triangles = self
+            .vertices
+            .chunks_exact(3)
+            .map(|t| {
+                [0, 1, 2].map(|i| {
+                    *index.entry(key(&t[i])).or_insert_with(|| {
+                        vertices.push(t[i].clone());
+                        vertices.len() - 1
+                    })
+                })
+            })
+            .collect();
+        (vertices, triangles)
+    }
+
+    /// Get the mesh as a table of unique vertices and triangles indexing into it. Vertices are
+    /// only merged when their coordinates are bit-for-bit identical, which the generators
+    /// guarantee for the corners that neighboring triangles share. Triangle order and winding
+    /// are kept.
+    pub fn to_indexed(&self) -> (Vec<Vec3>, Vec<[usize; 3]>) {
+        self.index_by(|v| [v.x.to_bits(), v.y.to_bits(), v.z.to_bits()])
+    }
+
+    /// Get the mesh as a Wavefront OBJ, with each shared vertex written once. Usually much
+    /// smaller than an STL of the same mesh.
+    pub fn as_obj_bytes(&self) -> Vec<u8> {
+        write_obj(self.to_indexed()).into_bytes()
+    }
+
+    /// Get the mesh as a Wavefront OBJ, welding vertices within about 1e-5 of each other so
+    /// corners that differ only by rounding are shared too. Faces keep the winding of the
+    /// original triangles.
+    pub fn as_obj(&self) -> String {
+        write_obj(self.index_by(vertex_key))
+    }
+}
+
+/// Write an indexed mesh as `v` and `f` lines
+fn write_obj((vertices, triangles): Indexed) -> String {
+    let mut obj = String::new();
+    // Writing to a String can't fail
+    for v in &vertices {
+        let _ = writeln!(obj, "v {} {} {}", v.x, v.y, v.z);
+    }
+    for [a, b, c] in triangles {
+        // OBJ indices start at 1
+        let _ = writeln!(obj, "f {} {} {}", a + 1, b + 1, c + 1);
+    }
+    obj
+}
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{vertex_key, Mesh, Vec3};
+
+/// Tolerance used to ignore triangles that only touch along an edge or at a vertex
+const EPSILON: f32 = 1e-5;
+
+/// Largest number of grid cells along any axis of the bounding box
+const MAX_CELLS: f32 = 128.0;
+
+type Bounds = ([f32; 3], [f32; 3]);
+
+fn bounds(t: &[Vec3]) -> Bounds {
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    for v in t {
+        for (i, c) in [v.x, v.y, v.z].into_iter().enumerate() {
+            min[i] = min[i].min(c);
+            max[i] = max[i].max(c);
+        }
+    }
+    (min, max)
+}
+
+fn overlaps(a: &Bounds, b: &Bounds) -> bool {
+    (0..3).all(|i| a.0[i] <= b.1[i] + EPSILON && b.0[i] <= a.1[i] + EPSILON)
+}
+
+/// Check whether the segment from `p` to `q` passes through the interior of triangle `t`
+pub(super) fn segment_hits_triangle(p: &Vec3, q: &Vec3, t: &[Vec3]) -> bool {
+    let dir = q - p;
+    let e1 = &t[1] - &t[0];
+    let e2 = &t[2] - &t[0];
+    let h = dir.cross(&e2);
+    let det = e1.dot(&h);
+    // Segments parallel to the triangle are left to the other triangle's edges
+    if det.abs() < EPSILON * EPSILON {
+        return false;
+    }
+    let s = p - &t[0];
+    let u = s.dot(&h) / det;
+    let qv = s.cross(&e1);
+    let v = dir.dot(&qv) / det;
+    let along = e2.dot(&qv) / det;
+    u > EPSILON && v > EPSILON && u + v < 1.0 - EPSILON && along > EPSILON && along < 1.0 - EPSILON
+}
+
+fn triangles_intersect(a: &[Vec3], b: &[Vec3]) -> bool {
+    let edges_hit = |t: &[Vec3], other: &[Vec3]| {
+        (0..3).any(|i| segment_hits_triangle(&t[i], &t[(i + 1) % 3], other))
+    };
+    edges_hit(a, b) || edges_hit(b, a)
+}
+
+impl Mesh {
+    /// Find pairs of triangles that pass through each other, returned as indices of the
+    /// triangles in the mesh with the smaller index first.
+    ///
+    /// Candidate pairs are found with a uniform grid over the bounding box, so only nearby
+    /// triangles are compared. Triangles sharing a vertex are neighbors and are never reported,
+    /// and coplanar overlaps aren't detected.
+    pub fn find_self_intersections(&self) -> Vec<(usize, usize)> {
+        let triangles: Vec<&[Vec3]> = self.vertices.chunks_exact(3).collect();
+        if triangles.len() < 2 {
+            return vec![];
+        }
+        let boxes: Vec<Bounds> = triangles.iter().map(|t| bounds(t)).collect();
+
+        let (min, max) = bounds(&self.vertices);
+        let extent = (0..3).map(|i| max[i] - min[i]).fold(0.0, f32::max);
+        // Size the cells to about one triangle, without letting the grid get too fine
+        let average = boxes
+            .iter()
+            .map(|(lo, hi)| (0..3).map(|i| hi[i] - lo[i]).fold(0.0, f32::max))
+            .sum::<f32>()
+            / boxes.len() as f32;
+        let cell = average.max(extent / MAX_CELLS).max(EPSILON);
+        let index = |c: f32, axis: usize| ((c - min[axis]) / cell).floor() as i32;
+
+        let mut grid: HashMap<[i32; 3], Vec<usize>> = HashMap::new();
+        for (i, (lo, hi)) in boxes.iter().enumerate() {
+            for x in index(lo[0], 0)..=index(hi[0], 0) {
+                for y in index(lo[1], 1)..=index(hi[1], 1) {
+                    for z in index(lo[2], 2)..=index(hi[2], 2) {
+                        grid.entry([x, y, z]).or_default().push(i);
+                    }
+                }
+            }
+        }
+
+        let keys: Vec<[_; 3]> = triangles
+            .iter()
+            .map(|t| [vertex_key(&t[0]), vertex_key(&t[1]), vertex_key(&t[2])])
+            .collect();
+        let mut checked = HashSet::new();
+        let mut found = vec![];
+        for cell in grid.values() {
+            for (n, &i) in cell.iter().enumerate() {
+                for &j in &cell[n + 1..] {
+                    let pair = (i.min(j), i.max(j));
+                    if !overlaps(&boxes[i], &boxes[j]) || !checked.insert(pair) {
+                        continue;
+                    }
+                    if keys[i].iter().any(|k| keys[j].contains(k)) {
+                        continue;
+                    }
+                    if triangles_intersect(triangles[i], triangles[j]) {
+                        found.push(pair);
+                    }
+                }
+            }
+        }
+
+        found.sort_unstable();
+        found
+    }
+}
@@ -0,0 +1,428 @@
+use std::{collections::HashMap, io::Write};
+
+use flate2::{write::GzEncoder, Compression};
+
+/// Simplifying meshes to a triangle budget
+mod decimate;
+/// Rough print time estimates
+mod estimate;
+/// Shared vertex tables and formats built on them
+mod indexed;
+/// Finding triangles that pass through each other
+mod intersect;
+/// Binary PLY export with vertex colors
+mod ply;
+/// Consolidated checks for problems that affect printing
+mod printability;
+/// Splitting meshes along a plane
+mod split;
+/// Moving, scaling and rotating meshes
+mod transform;
+
+pub use estimate::PrintEstimate;
+pub use printability::{Check, CheckStatus, Printability};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vec3 {
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let x = self.x.to_le_bytes();
+        let y = self.y.to_le_bytes();
+        let z = self.z.to_le_bytes();
+        [
+            x[0], x[1], x[2], x[3], y[0], y[1], y[2], y[3], z[0], z[1], z[2], z[3],
+        ]
+    }
+
+    /// Get the dot product of the two vectors
+    pub fn dot(&self, other: &Vec3) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Get the cross product of the two vectors, perpendicular to both by the right-hand rule
+    pub fn cross(&self, other: &Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    /// Get the length of the vector
+    pub fn length(&self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Get a vector of length 1 pointing the same way. The zero vector stays zero instead of
+    /// becoming NaN.
+    pub fn normalized(&self) -> Vec3 {
+        self * (1.0 / self.length().max(f32::EPSILON))
+    }
+}
+
+impl std::ops::Add<&Vec3> for &Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: &Vec3) -> Self::Output {
+        Vec3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl std::ops::Sub<&Vec3> for &Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: &Vec3) -> Self::Output {
+        Vec3 {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+impl std::ops::Mul<f32> for &Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: f32) -> Self::Output {
+        Vec3 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+trait CalcNormal {
+    fn normal(&self) -> Vec3;
+}
+
+impl CalcNormal for [Vec3; 3] {
+    fn normal(&self) -> Vec3 {
+        (&self[1] - &self[0]).cross(&(&self[2] - &self[0]))
+    }
+}
+
+/// Errors that can occur when building a mesh from user-supplied geometry
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshError {
+    /// The triangle at the given index has a NaN or infinite coordinate
+    NonFiniteVertex(usize),
+}
+
+impl std::fmt::Display for MeshError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeshError::NonFiniteVertex(i) => {
+                write!(f, "triangle {i} has a non-finite vertex coordinate")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
+
+/// Quantized vertex position, used to find shared vertices in the triangle soup
+type VertexKey = [i64; 3];
+
+/// Quantize a vertex so positions within about 1e-5 of each other compare equal
+fn vertex_key(v: &Vec3) -> VertexKey {
+    const SCALE: f32 = 1e5;
+    [
+        (v.x * SCALE).round() as i64,
+        (v.y * SCALE).round() as i64,
+        (v.z * SCALE).round() as i64,
+    ]
+}
+
+pub struct Mesh {
+    vertices: Vec<Vec3>,
+}
+
+impl Mesh {
+    pub fn new(vertices: Vec<Vec3>) -> Self {
+        Self { vertices }
+    }
+
+    /// Apply `f` to every vertex of the mesh
+    pub(crate) fn map_vertices(&mut self, f: impl Fn(&mut Vec3)) {
+        self.vertices.iter_mut().for_each(f);
+    }
+
+    /// Get the (min, max) corners of the axis-aligned bounding box of the mesh. An empty mesh
+    /// has both corners at the origin.
+    pub fn bounds(&self) -> (Vec3, Vec3) {
+        let Some(first) = self.vertices.first() else {
+            let origin = Vec3 {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+            return (origin.clone(), origin);
+        };
+        self.vertices
+            .iter()
+            .fold((first.clone(), first.clone()), |(min, max), v| {
+                (
+                    Vec3 {
+                        x: min.x.min(v.x),
+                        y: min.y.min(v.y),
+                        z: min.z.min(v.z),
+                    },
+                    Vec3 {
+                        x: max.x.max(v.x),
+                        y: max.y.max(v.y),
+                        z: max.z.max(v.z),
+                    },
+                )
+            })
+    }
+
+    /// Get the size of the bounding box along each axis, e.g. to check that the part fits on
+    /// the print bed
+    pub fn dimensions(&self) -> Vec3 {
+        let (min, max) = self.bounds();
+        &max - &min
+    }
+
+    /// Build a mesh from a list of triangles, rejecting any with non-finite coordinates.
+    pub fn try_from_triangles(triangles: Vec<[Vec3; 3]>) -> Result<Self, MeshError> {
+        let mut vertices = Vec::with_capacity(triangles.len() * 3);
+        for (i, t) in triangles.into_iter().enumerate() {
+            if t.iter()
+                .any(|v| !(v.x.is_finite() && v.y.is_finite() && v.z.is_finite()))
+            {
+                return Err(MeshError::NonFiniteVertex(i));
+            }
+            vertices.extend(t);
+        }
+        Ok(Self { vertices })
+    }
+
+    /// Append a single triangle to the mesh
+    pub fn push_triangle(&mut self, triangle: [Vec3; 3]) {
+        self.vertices.extend(triangle);
+    }
+
+    /// Get the volume enclosed by the mesh, summing the signed volume of the tetrahedron each
+    /// triangle forms with the origin. Only meaningful for closed meshes with outward normals.
+    pub fn volume(&self) -> f32 {
+        self.vertices
+            .chunks_exact(3)
+            .map(|t| t[0].dot(&t[1].cross(&t[2])))
+            .sum::<f32>()
+            / 6.0
+    }
+
+    /// Get the center of mass of the solid enclosed by the mesh, assuming uniform density. Each
+    /// triangle forms a tetrahedron with the origin as in `volume`, and their centroids are
+    /// averaged weighted by signed volume. Returns the origin for a mesh with no volume.
+    pub fn center_of_mass(&self) -> Vec3 {
+        let mut total = 0.0;
+        let mut sum = Vec3 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        for t in self.vertices.chunks_exact(3) {
+            let v = t[0].dot(&t[1].cross(&t[2])) / 6.0;
+            total += v;
+            // The tetrahedron's fourth vertex is the origin
+            sum.x += v * (t[0].x + t[1].x + t[2].x) / 4.0;
+            sum.y += v * (t[0].y + t[1].y + t[2].y) / 4.0;
+            sum.z += v * (t[0].z + t[1].z + t[2].z) / 4.0;
+        }
+        if total == 0.0 {
+            return sum;
+        }
+        Vec3 {
+            x: sum.x / total,
+            y: sum.y / total,
+            z: sum.z / total,
+        }
+    }
+
+    /// Move the mesh so its center of mass is at the origin. Unlike centering the bounding
+    /// box, this balances the part for stands and turntables.
+    pub fn recenter_on_com(&mut self) {
+        let com = self.center_of_mass();
+        self.map_vertices(|v| {
+            v.x -= com.x;
+            v.y -= com.y;
+            v.z -= com.z;
+        });
+    }
+
+    /// Count how many triangles use each undirected edge, keyed by the quantized endpoints
+    fn edge_uses(&self) -> HashMap<(VertexKey, VertexKey), (usize, &Vec3, &Vec3)> {
+        let mut edges = HashMap::new();
+        for t in self.vertices.chunks_exact(3) {
+            for (a, b) in [(&t[0], &t[1]), (&t[1], &t[2]), (&t[2], &t[0])] {
+                let (ka, kb) = (vertex_key(a), vertex_key(b));
+                if ka == kb {
+                    continue;
+                }
+                let key = if ka < kb { (ka, kb) } else { (kb, ka) };
+                edges.entry(key).or_insert((0, a, b)).0 += 1;
+            }
+        }
+        edges
+    }
+
+    /// Get the endpoints of every edge that is not shared by exactly two triangles. A closed,
+    /// manifold mesh has none. Vertices are matched by position, so duplicated vertices in the
+    /// triangle soup are treated as one.
+    pub fn non_manifold_edges(&self) -> Vec<(Vec3, Vec3)> {
+        let mut edges: Vec<_> = self
+            .edge_uses()
+            .into_iter()
+            .filter(|(_, (uses, _, _))| *uses != 2)
+            .collect();
+        edges.sort_by_key(|(key, _)| *key);
+        edges
+            .into_iter()
+            .map(|(_, (_, a, b))| (a.clone(), b.clone()))
+            .collect()
+    }
+
+    /// Get the endpoints of every edge used by only one triangle, the borders of holes in the
+    /// surface. Unlike `non_manifold_edges`, edges shared by three or more triangles are left out.
+    pub fn open_edges(&self) -> Vec<(Vec3, Vec3)> {
+        let mut edges: Vec<_> = self
+            .edge_uses()
+            .into_iter()
+            .filter(|(_, (uses, _, _))| *uses == 1)
+            .collect();
+        edges.sort_by_key(|(key, _)| *key);
+        edges
+            .into_iter()
+            .map(|(_, (_, a, b))| (a.clone(), b.clone()))
+            .collect()
+    }
+
+    /// Whether every edge is shared by exactly two triangles, so the mesh encloses a volume
+    /// with no holes. Vertices are matched by position, like `non_manifold_edges`.
+    pub fn is_watertight(&self) -> bool {
+        self.edge_uses().values().all(|(uses, _, _)| *uses == 2)
+    }
+
+    /// Build a mesh drawing each edge as a thin triangular prism of the given thickness, so
+    /// problem edges can be inspected alongside the model in any mesh viewer.
+    pub fn edges_debug_mesh(edges: &[(Vec3, Vec3)], thickness: f32) -> Mesh {
+        let mut vertices = vec![];
+        for (a, b) in edges {
+            let d = b - a;
+            // Any axis that isn't parallel to the edge works to build the cross section
+            let axis = if d.x.abs() < d.y.abs().max(d.z.abs()) {
+                Vec3 {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                }
+            } else {
+                Vec3 {
+                    x: 0.0,
+                    y: 1.0,
+                    z: 0.0,
+                }
+            };
+            let u = d.cross(&axis).normalized();
+            let v = d.cross(&u).normalized();
+            let ring = |p: &Vec3| {
+                [(1.0, 0.0), (-0.5, 0.866), (-0.5, -0.866)].map(|(cu, cv)| Vec3 {
+                    x: p.x + (u.x * cu + v.x * cv) * thickness,
+                    y: p.y + (u.y * cu + v.y * cv) * thickness,
+                    z: p.z + (u.z * cu + v.z * cv) * thickness,
+                })
+            };
+            let (ra, rb) = (ring(a), ring(b));
+            for i in 0..3 {
+                let j = (i + 1) % 3;
+                vertices.extend_from_slice(&[ra[i].clone(), ra[j].clone(), rb[j].clone()]);
+                vertices.extend_from_slice(&[ra[i].clone(), rb[j].clone(), rb[i].clone()]);
+            }
+            vertices.extend_from_slice(&[ra[0].clone(), ra[2].clone(), ra[1].clone()]);
+            vertices.extend_from_slice(&[rb[0].clone(), rb[1].clone(), rb[2].clone()]);
+        }
+        Mesh::new(vertices)
+    }
+
+    /// Get the mesh as a binary STL, built in memory. Prefer [`Mesh::write_stl`] when saving
+    /// large meshes to a file.
+    pub fn as_stl_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(84 + self.vertices.len() / 3 * 50);
+        self.write_stl(&mut bytes)
+            .expect("writing to a Vec can't fail");
+        bytes
+    }
+
+    /// Write the mesh as a binary STL to `w` one facet at a time, without building the whole
+    /// file in memory. Wrap files in a `BufWriter`, since each facet is a separate write.
+    pub fn write_stl<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        w.write_all(&[0; 80])?;
+        w.write_all(&((self.vertices.len() / 3) as u32).to_le_bytes())?;
+
+        let mut record = [0; 50];
+        for t in self.vertices.chunks_exact(3) {
+            let t: &[Vec3; 3] = t.try_into().unwrap();
+            record[..12].copy_from_slice(&t.normal().to_bytes());
+            for (i, v) in t.iter().enumerate() {
+                record[12 + i * 12..24 + i * 12].copy_from_slice(&v.to_bytes());
+            }
+            w.write_all(&record)?;
+        }
+        Ok(())
+    }
+
+    /// Get the mesh as an ASCII STL with the solid called `lithophane`
+    pub fn as_ascii_stl(&self) -> String {
+        self.as_ascii_stl_string("lithophane")
+    }
+
+    /// Get the mesh as an ASCII STL with the solid called `name`. Much larger than the binary
+    /// format, but readable and easy to diff. Coordinates are written with 7 significant
+    /// digits, and facet normals are scaled to unit length since many slicers warn otherwise.
+    pub fn as_ascii_stl_string(&self, name: &str) -> String {
+        use std::fmt::Write;
+
+        let mut stl = format!("solid {name}\n");
+        self.vertices.chunks_exact(3).for_each(|t| {
+            let t: &[Vec3; 3] = t.try_into().unwrap();
+            let n = t.normal().normalized();
+            // Writing to a String can't fail
+            let _ = writeln!(stl, "  facet normal {:.6e} {:.6e} {:.6e}", n.x, n.y, n.z);
+            stl.push_str("    outer loop\n");
+            for v in t {
+                let _ = writeln!(stl, "      vertex {:.6e} {:.6e} {:.6e}", v.x, v.y, v.z);
+            }
+            stl.push_str("    endloop\n  endfacet\n");
+        });
+        let _ = writeln!(stl, "endsolid {name}");
+        stl
+    }
+
+    /// Get the binary STL of the mesh compressed with gzip, to be saved as `.stl.gz`. Detailed
+    /// lithophanes compress to a fraction of their size since most of the normals repeat.
+    pub fn as_stl_gz_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        self.write_stl_gz(&mut bytes)
+            .expect("writing to a Vec can't fail");
+        bytes
+    }
+
+    /// Write the mesh as a gzip-compressed binary STL to `w`, streaming like `write_stl`
+    pub fn write_stl_gz<W: Write>(&self, w: W) -> std::io::Result<()> {
+        let mut encoder = GzEncoder::new(w, Compression::default());
+        self.write_stl(&mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
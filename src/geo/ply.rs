@@ -0,0 +1,41 @@
+use super::{Mesh, Vec3};
+
+impl Mesh {
+    /// Get the mesh as a little-endian binary PLY with shared vertices, coloring each vertex
+    /// gray by `lightness(vertex)`: 0.0 is black and 1.0 is white, with values outside that
+    /// range clamped. Mainly useful for checking how lightness was mapped onto the relief.
+    pub fn as_ply_bytes(&self, lightness: impl Fn(&Vec3) -> f32) -> Vec<u8> {
+        let (vertices, triangles) = self.to_indexed();
+        let header = format!(
+            "ply\n\
+             format binary_little_endian 1.0\n\
+             element vertex {}\n\
+             property float x\n\
+             property float y\n\
+             property float z\n\
+             property uchar red\n\
+             property uchar green\n\
+             property uchar blue\n\
+             element face {}\n\
+             property list uchar uint vertex_indices\n\
+             end_header\n",
+            vertices.len(),
+            triangles.len()
+        );
+
+        let mut bytes = header.into_bytes();
+        bytes.reserve(vertices.len() * 15 + triangles.len() * 13);
+        for v in &vertices {
+            bytes.extend_from_slice(&v.to_bytes());
+            let gray = (lightness(v).clamp(0.0, 1.0) * 255.0).round() as u8;
+            bytes.extend_from_slice(&[gray; 3]);
+        }
+        for t in &triangles {
+            bytes.push(3);
+            for &i in t {
+                bytes.extend_from_slice(&(i as u32).to_le_bytes());
+            }
+        }
+        bytes
+    }
+}
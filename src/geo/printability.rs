@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use super::{intersect::segment_hits_triangle, CalcNormal, Mesh, Vec3};
+
+/// Steepest overhang, measured from vertical, that prints cleanly without supports
+const MAX_OVERHANG_DEGREES: f32 = 45.0;
+
+/// Tolerance used to find triangles resting on the print bed
+const EPSILON: f32 = 1e-5;
+
+/// How far outside the surface thin wall rays start, as a fraction of the minimum wall
+const RAY_LEAD: f32 = 1e-3;
+
+/// Outcome of a single printability check, ordered from best to worst
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Pass,
+    /// The part will likely print, but may come out rough or fragile
+    Warn,
+    /// The part is broken in a way that slicers may not recover from
+    Fail,
+}
+
+impl std::fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                CheckStatus::Pass => "pass",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail => "fail",
+            }
+        )
+    }
+}
+
+/// The result of one printability check, with the number of problems it found
+#[derive(Clone, Debug, PartialEq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub count: usize,
+    /// What `count` is counting, e.g. "open edges"
+    pub unit: &'static str,
+}
+
+impl Check {
+    /// Build a check that reports `status` if it found any problems and passes otherwise
+    fn new(name: &'static str, unit: &'static str, count: usize, status: CheckStatus) -> Self {
+        Self {
+            name,
+            status: if count == 0 {
+                CheckStatus::Pass
+            } else {
+                status
+            },
+            count,
+            unit,
+        }
+    }
+}
+
+impl std::fmt::Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} ({} {})",
+            self.name, self.status, self.count, self.unit
+        )
+    }
+}
+
+/// Every printability check run on a mesh, in the order they should be shown
+#[derive(Clone, Debug, PartialEq)]
+pub struct Printability {
+    pub checks: Vec<Check>,
+}
+
+impl Printability {
+    /// Get the worst status of any check
+    pub fn status(&self) -> CheckStatus {
+        self.checks
+            .iter()
+            .map(|c| c.status)
+            .max()
+            .unwrap_or(CheckStatus::Pass)
+    }
+
+    /// Get the check with the given name, if it was run
+    pub fn check(&self, name: &str) -> Option<&Check> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+}
+
+impl Mesh {
+    /// Run every printability check on the mesh, standing as it is with +Z up. Walls thinner
+    /// than `min_wall` mm are reported as thin.
+    ///
+    /// Holes and self-intersections fail, since they leave the slicer guessing at what is
+    /// inside the part. Overhangs and thin walls only warn, since they still print, just
+    /// not well.
+    pub fn printability(&self, min_wall: f32) -> Printability {
+        Printability {
+            checks: vec![
+                Check::new(
+                    "Watertight",
+                    "open edges",
+                    self.non_manifold_edges().len(),
+                    CheckStatus::Fail,
+                ),
+                Check::new(
+                    "Self-intersections",
+                    "intersecting pairs",
+                    self.find_self_intersections().len(),
+                    CheckStatus::Fail,
+                ),
+                Check::new(
+                    "Overhangs",
+                    "triangles",
+                    self.overhanging_triangles(MAX_OVERHANG_DEGREES),
+                    CheckStatus::Warn,
+                ),
+                Check::new(
+                    "Thin walls",
+                    "triangles",
+                    self.thin_wall_triangles(min_wall),
+                    CheckStatus::Warn,
+                ),
+            ],
+        }
+    }
+
+    /// Get the unit outward normal of every triangle. Normals are flipped for meshes wound
+    /// inside out, which the volume reveals.
+    fn outward_normals(&self) -> Vec<Vec3> {
+        let sign = if self.volume() < 0.0 { -1.0 } else { 1.0 };
+        self.vertices
+            .chunks_exact(3)
+            .map(|t| {
+                let t: &[Vec3; 3] = t.try_into().unwrap();
+                &t.normal().normalized() * sign
+            })
+            .collect()
+    }
+
+    /// Count the triangles that face down more steeply than `degrees` from vertical, leaving
+    /// out the ones lying on the bed
+    fn overhanging_triangles(&self, degrees: f32) -> usize {
+        let (min, _) = self.bounds();
+        let limit = -degrees.to_radians().cos();
+        self.vertices
+            .chunks_exact(3)
+            .zip(self.outward_normals())
+            .filter(|(t, n)| n.z < limit && t.iter().any(|v| v.z > min.z + EPSILON))
+            .count()
+    }
+
+    /// Count the triangles with another part of the surface less than `min_wall` behind them,
+    /// by casting a ray inward from the center of each triangle
+    fn thin_wall_triangles(&self, min_wall: f32) -> usize {
+        if min_wall <= 0.0 {
+            return 0;
+        }
+        let triangles: Vec<&[Vec3]> = self.vertices.chunks_exact(3).collect();
+        let normals = self.outward_normals();
+
+        // The rays are only `min_wall` long, so a grid of that size finds every triangle a ray
+        // could reach in the cells around it
+        let index = |v: &Vec3| [v.x, v.y, v.z].map(|c| (c / min_wall).floor() as i32);
+        let mut grid: HashMap<[i32; 3], Vec<usize>> = HashMap::new();
+        for (i, t) in triangles.iter().enumerate() {
+            let cells: Vec<_> = t.iter().map(index).collect();
+            let lo = [0, 1, 2].map(|a| cells.iter().map(|c| c[a]).min().unwrap());
+            let hi = [0, 1, 2].map(|a| cells.iter().map(|c| c[a]).max().unwrap());
+            for x in lo[0]..=hi[0] {
+                for y in lo[1]..=hi[1] {
+                    for z in lo[2]..=hi[2] {
+                        grid.entry([x, y, z]).or_default().push(i);
+                    }
+                }
+            }
+        }
+
+        triangles
+            .iter()
+            .zip(&normals)
+            .enumerate()
+            .filter(|(i, (t, n))| {
+                // Start just outside the surface so walls with no thickness at all are hit
+                let lead = min_wall * RAY_LEAD;
+                let start = Vec3 {
+                    x: (t[0].x + t[1].x + t[2].x) / 3.0 + n.x * lead,
+                    y: (t[0].y + t[1].y + t[2].y) / 3.0 + n.y * lead,
+                    z: (t[0].z + t[1].z + t[2].z) / 3.0 + n.z * lead,
+                };
+                let end = &start - &(*n * min_wall);
+                let (a, b) = (index(&start), index(&end));
+                (a[0].min(b[0])..=a[0].max(b[0])).any(|x| {
+                    (a[1].min(b[1])..=a[1].max(b[1])).any(|y| {
+                        (a[2].min(b[2])..=a[2].max(b[2])).any(|z| {
+                            grid.get(&[x, y, z]).is_some_and(|cell| {
+                                cell.iter().any(|&j| {
+                                    j != *i
+                                        // Only surfaces facing back toward this one bound a wall
+                                        && n.dot(&normals[j]) < 0.0
+                                        && segment_hits_triangle(&start, &end, triangles[j])
+                                })
+                            })
+                        })
+                    })
+                })
+            })
+            .count()
+    }
+}
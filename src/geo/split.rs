@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+
+use super::{vertex_key, Mesh, Vec3, VertexKey};
+
+/// A point on the cutting plane with its 2D coordinates in the plane
+struct CapPoint {
+    pos: Vec3,
+    uv: (f32, f32),
+}
+
+impl Mesh {
+    /// Split the mesh along the plane through `point` with the given `normal`. Returns the
+    /// part on the side the normal points to, followed by the part behind the plane.
+    ///
+    /// Triangles crossing the plane are clipped, and the cut is capped on both halves so a
+    /// closed input mesh produces two closed meshes.
+    pub fn split_by_plane(&self, point: &Vec3, normal: &Vec3) -> (Mesh, Mesh) {
+        let n = normal.normalized();
+        let distance = |v: &Vec3| (v - point).dot(&n);
+
+        let mut front = vec![];
+        let mut back = vec![];
+        // Pieces of the cut, ordered so the loops they form wind counter-clockwise around the
+        // normal for the back half
+        let mut segments = vec![];
+
+        for t in self.vertices.chunks_exact(3) {
+            let d = [distance(&t[0]), distance(&t[1]), distance(&t[2])];
+            let in_front = d.map(|d| d >= 0.0);
+            if in_front.iter().all(|&f| f) {
+                front.extend_from_slice(t);
+                continue;
+            }
+            if in_front.iter().all(|&f| !f) {
+                back.extend_from_slice(t);
+                continue;
+            }
+
+            let mut front_poly = vec![];
+            let mut back_poly = vec![];
+            let mut exit = None;
+            let mut entry = None;
+            for i in 0..3 {
+                let j = (i + 1) % 3;
+                if in_front[i] {
+                    front_poly.push(t[i].clone());
+                } else {
+                    back_poly.push(t[i].clone());
+                }
+                if in_front[i] == in_front[j] {
+                    continue;
+                }
+
+                // Always interpolate from the front vertex so both triangles sharing this edge
+                // compute the exact same point
+                let (f, b) = if in_front[i] { (i, j) } else { (j, i) };
+                let s = d[f] / (d[f] - d[b]);
+                let p = Vec3 {
+                    x: t[f].x + (t[b].x - t[f].x) * s,
+                    y: t[f].y + (t[b].y - t[f].y) * s,
+                    z: t[f].z + (t[b].z - t[f].z) * s,
+                };
+                front_poly.push(p.clone());
+                back_poly.push(p.clone());
+                if in_front[i] {
+                    exit = Some(p);
+                } else {
+                    entry = Some(p);
+                }
+            }
+
+            fan(&front_poly, &mut front);
+            fan(&back_poly, &mut back);
+            if let (Some(exit), Some(entry)) = (exit, entry) {
+                segments.push((exit, entry));
+            }
+        }
+
+        for cap in cap_triangles(&segments, &n) {
+            let [a, b, c] = cap;
+            back.extend_from_slice(&[a.clone(), b.clone(), c.clone()]);
+            front.extend_from_slice(&[a, c, b]);
+        }
+
+        (Mesh::new(front), Mesh::new(back))
+    }
+}
+
+/// Triangulate a convex polygon as a fan around its first vertex
+fn fan(poly: &[Vec3], out: &mut Vec<Vec3>) {
+    for i in 1..poly.len().saturating_sub(1) {
+        out.extend_from_slice(&[poly[0].clone(), poly[i].clone(), poly[i + 1].clone()]);
+    }
+}
+
+/// Chain the cut segments into loops and triangulate them, with every triangle facing `n`
+fn cap_triangles(segments: &[(Vec3, Vec3)], n: &Vec3) -> Vec<[Vec3; 3]> {
+    // Basis for the plane with u x v = n, so counter-clockwise in uv means facing n
+    let axis = if n.x.abs() < 0.9 {
+        Vec3 {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    } else {
+        Vec3 {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        }
+    };
+    let u = n.cross(&axis).normalized();
+    let v = n.cross(&u);
+
+    let mut points: Vec<CapPoint> = vec![];
+    let mut ids: HashMap<VertexKey, usize> = HashMap::new();
+    let mut id = |p: &Vec3, points: &mut Vec<CapPoint>| {
+        *ids.entry(vertex_key(p)).or_insert_with(|| {
+            points.push(CapPoint {
+                pos: p.clone(),
+                uv: (p.dot(&u), p.dot(&v)),
+            });
+            points.len() - 1
+        })
+    };
+
+    let mut next: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (a, b) in segments {
+        let (a, b) = (id(a, &mut points), id(b, &mut points));
+        if a != b {
+            next.entry(a).or_default().push(b);
+        }
+    }
+
+    // Follow the segments until each chain returns to where it started
+    let mut loops = vec![];
+    let mut starts: Vec<usize> = next.keys().copied().collect();
+    starts.sort_unstable();
+    for start in starts {
+        while let Some(mut current) = next.get_mut(&start).and_then(|n| n.pop()) {
+            let mut poly = vec![start];
+            while current != start {
+                poly.push(current);
+                match next.get_mut(&current).and_then(|n| n.pop()) {
+                    Some(n) => current = n,
+                    // An open chain means the input wasn't closed, so nothing can be capped
+                    None => break,
+                }
+            }
+            if current == start && poly.len() >= 3 {
+                loops.push(poly);
+            }
+        }
+    }
+
+    // An inside-out mesh produces loops wound the other way, so triangulate them flipped and
+    // flip the result back to match the rest of the mesh
+    let inverted = loops.iter().map(|l| signed_area(l, &points)).sum::<f32>() < 0.0;
+    if inverted {
+        loops.iter_mut().for_each(|l| l.reverse());
+    }
+
+    let (outers, holes): (Vec<_>, Vec<_>) = loops
+        .into_iter()
+        .partition(|l| signed_area(l, &points) > 0.0);
+
+    // Give each hole to the smallest outer loop containing it
+    let mut polygons: Vec<(Vec<usize>, Vec<Vec<usize>>)> =
+        outers.into_iter().map(|o| (o, vec![])).collect();
+    for hole in holes {
+        let p = points[hole[0]].uv;
+        let owner = polygons
+            .iter_mut()
+            .filter(|(outer, _)| contains(outer, &points, p))
+            .min_by(|(a, _), (b, _)| signed_area(a, &points).total_cmp(&signed_area(b, &points)));
+        if let Some((_, holes)) = owner {
+            holes.push(hole);
+        }
+    }
+
+    let mut tris = vec![];
+    for (outer, holes) in polygons {
+        let poly = bridge_holes(outer, holes, &points);
+        for [a, b, c] in ear_clip(poly, &points) {
+            let (b, c) = if inverted { (c, b) } else { (b, c) };
+            tris.push([
+                points[a].pos.clone(),
+                points[b].pos.clone(),
+                points[c].pos.clone(),
+            ]);
+        }
+    }
+    tris
+}
+
+fn signed_area(poly: &[usize], points: &[CapPoint]) -> f32 {
+    let mut area = 0.0;
+    for i in 0..poly.len() {
+        let (a, b) = (points[poly[i]].uv, points[poly[(i + 1) % poly.len()]].uv);
+        area += a.0 * b.1 - b.0 * a.1;
+    }
+    area / 2.0
+}
+
+/// Even-odd test for whether `p` is inside the polygon
+fn contains(poly: &[usize], points: &[CapPoint], p: (f32, f32)) -> bool {
+    let mut inside = false;
+    for i in 0..poly.len() {
+        let (a, b) = (points[poly[i]].uv, points[poly[(i + 1) % poly.len()]].uv);
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn orient(a: (f32, f32), b: (f32, f32), c: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Whether the segments p1-p2 and q1-q2 cross at a point that isn't one of their endpoints
+fn segments_cross(p1: (f32, f32), p2: (f32, f32), q1: (f32, f32), q2: (f32, f32)) -> bool {
+    if p1 == q1 || p1 == q2 || p2 == q1 || p2 == q2 {
+        return false;
+    }
+    let (d1, d2) = (orient(q1, q2, p1), orient(q1, q2, p2));
+    let (d3, d4) = (orient(p1, p2, q1), orient(p1, p2, q2));
+    (d1 > 0.0) != (d2 > 0.0) && (d3 > 0.0) != (d4 > 0.0)
+}
+
+/// Merge the (clockwise) holes into the (counter-clockwise) outer loop by cutting a bridge
+/// from each hole to a vertex it can see, giving one simple polygon to ear clip.
+fn bridge_holes(
+    mut outer: Vec<usize>,
+    mut holes: Vec<Vec<usize>>,
+    points: &[CapPoint],
+) -> Vec<usize> {
+    let rightmost = |hole: &[usize]| {
+        (0..hole.len())
+            .max_by(|&a, &b| points[hole[a]].uv.0.total_cmp(&points[hole[b]].uv.0))
+            .unwrap()
+    };
+    holes.sort_by(|a, b| {
+        let (a, b) = (points[a[rightmost(a)]].uv.0, points[b[rightmost(b)]].uv.0);
+        b.total_cmp(&a)
+    });
+
+    for h in 0..holes.len() {
+        let hole = &holes[h];
+        let m = rightmost(hole);
+        let mp = points[hole[m]].uv;
+
+        let edges = |poly: &[usize]| {
+            (0..poly.len())
+                .map(|i| (poly[i], poly[(i + 1) % poly.len()]))
+                .collect::<Vec<_>>()
+        };
+        let mut blockers = edges(&outer);
+        for other in &holes[h..] {
+            blockers.extend(edges(other));
+        }
+
+        let distance = |i: usize| {
+            let p = points[outer[i]].uv;
+            (p.0 - mp.0).powi(2) + (p.1 - mp.1).powi(2)
+        };
+        let mut candidates: Vec<usize> = (0..outer.len()).collect();
+        candidates.sort_by(|&a, &b| distance(a).total_cmp(&distance(b)));
+        let visible = candidates.into_iter().find(|&i| {
+            let pp = points[outer[i]].uv;
+            !blockers
+                .iter()
+                .any(|&(a, b)| segments_cross(mp, pp, points[a].uv, points[b].uv))
+        });
+
+        if let Some(i) = visible {
+            let mut merged = outer[..=i].to_vec();
+            merged.extend(hole[m..].iter().chain(&hole[..=m]));
+            merged.push(outer[i]);
+            merged.extend_from_slice(&outer[i + 1..]);
+            outer = merged;
+        }
+    }
+
+    outer
+}
+
+/// Triangulate a simple counter-clockwise polygon by repeatedly cutting off ears
+fn ear_clip(mut poly: Vec<usize>, points: &[CapPoint]) -> Vec<[usize; 3]> {
+    let uv = |i: usize| points[i].uv;
+    let mut tris = vec![];
+    let mut i = 0;
+    let mut misses = 0;
+
+    while poly.len() > 3 {
+        let len = poly.len();
+        let (a, b, c) = (
+            poly[(i + len - 1) % len],
+            poly[i % len],
+            poly[(i + 1) % len],
+        );
+        let (pa, pb, pc) = (uv(a), uv(b), uv(c));
+
+        let is_ear = orient(pa, pb, pc) > 0.0
+            && !poly.iter().any(|&p| {
+                let pp = uv(p);
+                if pp == pa || pp == pb || pp == pc {
+                    return false;
+                }
+                orient(pa, pb, pp) >= 0.0 && orient(pb, pc, pp) >= 0.0 && orient(pc, pa, pp) >= 0.0
+            });
+
+        // If no ear can be found the polygon is degenerate, so cut the vertex anyway to
+        // guarantee progress
+        if is_ear || misses > len {
+            if orient(pa, pb, pc) > 0.0 {
+                tris.push([a, b, c]);
+            }
+            poly.remove(i % len);
+            misses = 0;
+            i %= poly.len();
+        } else {
+            misses += 1;
+            i = (i + 1) % len;
+        }
+    }
+
+    if poly.len() == 3 && orient(uv(poly[0]), uv(poly[1]), uv(poly[2])) > 0.0 {
+        tris.push([poly[0], poly[1], poly[2]]);
+    }
+    tris
+}
@@ -0,0 +1,34 @@
+use super::{Mesh, Vec3};
+
+impl Mesh {
+    /// Move every vertex of the mesh by `offset`
+    pub fn translate(&mut self, offset: &Vec3) {
+        self.map_vertices(|v| {
+            v.x += offset.x;
+            v.y += offset.y;
+            v.z += offset.z;
+        });
+    }
+
+    /// Scale the mesh about the origin by a separate factor along each axis. Mirroring along
+    /// an odd number of axes would turn the mesh inside out, so the winding of every triangle
+    /// is reversed to keep the normals pointing outward.
+    pub fn scale(&mut self, factor: Vec3) {
+        self.map_vertices(|v| {
+            v.x *= factor.x;
+            v.y *= factor.y;
+            v.z *= factor.z;
+        });
+        if factor.x * factor.y * factor.z < 0.0 {
+            self.vertices.chunks_exact_mut(3).for_each(|t| t.swap(1, 2));
+        }
+    }
+
+    /// Rotate the mesh counterclockwise by `radians` about the z axis, looking down from +z
+    pub fn rotate_z(&mut self, radians: f32) {
+        let (sin, cos) = radians.sin_cos();
+        self.map_vertices(|v| {
+            (v.x, v.y) = (v.x * cos - v.y * sin, v.x * sin + v.y * cos);
+        });
+    }
+}
@@ -7,19 +7,96 @@ pub fn srgb_to_linear(s: f32) -> f32 {
     }
 }
 
+/// Convert a linear RGB value l to its sRGB equivalent
+pub fn linear_to_srgb(l: f32) -> f32 {
+    if l <= 0.0031308 {
+        l * 12.92
+    } else {
+        1.055 * l.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// How much each linear channel contributes to luminance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LuminanceWeights {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl LuminanceWeights {
+    /// The weights for sRGB and HDTV primaries, used by default
+    pub const REC709: LuminanceWeights = LuminanceWeights {
+        r: 0.2126,
+        g: 0.7152,
+        b: 0.0722,
+    };
+
+    /// The older SDTV weights, which many other lithophane tools still use
+    pub const REC601: LuminanceWeights = LuminanceWeights {
+        r: 0.299,
+        g: 0.587,
+        b: 0.114,
+    };
+
+    /// The weights for UHDTV's wide gamut primaries
+    pub const REC2020: LuminanceWeights = LuminanceWeights {
+        r: 0.2627,
+        g: 0.678,
+        b: 0.0593,
+    };
+}
+
+impl Default for LuminanceWeights {
+    fn default() -> Self {
+        LuminanceWeights::REC709
+    }
+}
+
 /// Get the luminance Y of an sRGB pixel slice
 pub fn srgb_to_luminance(pixel: &[u8]) -> f32 {
+    srgb_to_luminance_gamma(pixel, 1.0)
+}
+
+/// Get the luminance Y of an sRGB pixel slice, summing the linear channels with `weights`
+pub fn srgb_to_luminance_weighted(pixel: &[u8], weights: LuminanceWeights) -> f32 {
+    normalized_srgb_to_luminance(normalize_rgb8(pixel), 1.0, weights)
+}
+
+/// Get the luminance Y of an sRGB pixel slice, raising each linear channel to `gamma` before
+/// the channels are summed
+pub fn srgb_to_luminance_gamma(pixel: &[u8], gamma: f32) -> f32 {
+    normalized_srgb_to_luminance(normalize_rgb8(pixel), gamma, LuminanceWeights::REC709)
+}
+
+/// Scale the channels of an 8-bit sRGB pixel slice to \[0, 1]
+fn normalize_rgb8(pixel: &[u8]) -> [f32; 3] {
     assert_eq!(
         pixel.len(),
         3,
         "Pixel length was not 3. Did you really pass in a pixel slice?"
     );
-    let (r, g, b) = (
+    [
         pixel[0] as f32 / 255.0,
         pixel[1] as f32 / 255.0,
         pixel[2] as f32 / 255.0,
-    );
-    srgb_to_linear(r) * 0.2126 + srgb_to_linear(g) * 0.7152 + srgb_to_linear(b) * 0.0722
+    ]
+}
+
+/// Get the luminance Y of an sRGB pixel whose channels are already normalized to \[0, 1],
+/// whatever their original bit depth, raising each linear channel to `gamma` before the
+/// channels are summed with `weights`
+pub fn normalized_srgb_to_luminance(rgb: [f32; 3], gamma: f32, weights: LuminanceWeights) -> f32 {
+    let [r, g, b] = rgb;
+    let linear = |c: f32| {
+        let l = srgb_to_linear(c);
+        if gamma == 1.0 {
+            l
+        } else {
+            l.powf(gamma)
+        }
+    };
+    linear(r) * weights.r + linear(g) * weights.g + linear(b) * weights.b
 }
 
 /// Convert a gamma value on the interval \[0, 255] to a percieved lightness value.
@@ -31,3 +108,306 @@ pub fn luminance_to_lightness(y: f32) -> f32 {
         (y.powf(1.0 / 3.0) * 116.0) - 16.0
     }
 }
+
+/// Settings for converting sRGB pixels into the lightness values stored in a `LightMap`.
+///
+/// A pixel goes through these steps in order:
+/// 1. each channel is converted from sRGB to linear light
+/// 2. `linear_gamma` is applied to each linear channel
+/// 3. the channels are summed into luminance with `weights`, Rec. 709 by default
+/// 4. luminance is mapped to perceived lightness and normalized to \[0, 1], unless
+///    `perceptual` is off, in which case the luminance is used as the lightness directly
+/// 5. if `alpha_relief` is on, the lightness `l` of a pixel with alpha `a` in \[0, 1] becomes
+///    `1 - a * (1 - l)`, which multiplies its relief thickness by its opacity
+/// 6. if `invert` is on, the lightness `l` becomes `1 - l`, so light areas get the relief
+/// 7. pixels with an alpha below `alpha_threshold` get `transparent_lightness` instead, 1 by
+///    default, which leaves them at the base
+///
+/// Tone adjustments made by a preprocessor on the resulting lightness happen after all of
+/// these, so a linear gamma behaves differently from a gamma on the final lightness.
+#[derive(Clone, Debug)]
+pub struct LightnessConversion {
+    linear_gamma: f32,
+    alpha_relief: bool,
+    perceptual: bool,
+    invert: bool,
+    alpha_threshold: f32,
+    weights: LuminanceWeights,
+    transparent_lightness: f32,
+}
+
+impl LightnessConversion {
+    /// Set the gamma applied to the linear channels of each pixel
+    pub fn linear_gamma(mut self, gamma: f32) -> Self {
+        self.linear_gamma = gamma;
+        self
+    }
+
+    /// Set the lightness given to pixels below the alpha threshold. The default of 1.0 leaves
+    /// them at the base; 0.0 gives them the full relief instead.
+    pub fn transparent_lightness(mut self, lightness: f32) -> Self {
+        self.transparent_lightness = lightness;
+        self
+    }
+
+    /// Set the weights used to sum the linear channels into luminance
+    pub fn weights(mut self, weights: LuminanceWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Scale the relief of each pixel by its alpha, so transparent areas come out thinner
+    pub fn alpha_relief(mut self, enabled: bool) -> Self {
+        self.alpha_relief = enabled;
+        self
+    }
+
+    /// Map luminance through the CIE lightness curve. When off, linear luminance is used as
+    /// the lightness as-is, which gives much less relief to the shadows.
+    pub fn perceptual(mut self, enabled: bool) -> Self {
+        self.perceptual = enabled;
+        self
+    }
+
+    /// Swap light and dark as the last step, for front-lit pieces where bright areas should be
+    /// the thick ones. Only the RGBA conversions apply it.
+    pub fn invert(mut self, enabled: bool) -> Self {
+        self.invert = enabled;
+        self
+    }
+
+    /// Treat pixels with an alpha below `threshold`, in \[0, 1], as empty and give them no
+    /// relief, so cut-out backgrounds drop to the base. Only the RGBA conversions apply it.
+    pub fn alpha_threshold(mut self, threshold: f32) -> Self {
+        self.alpha_threshold = threshold;
+        self
+    }
+
+    /// Get the lightness in \[0, 1] of an sRGB pixel slice
+    pub fn lightness(&self, pixel: &[u8]) -> f32 {
+        self.lightness_normalized(normalize_rgb8(pixel))
+    }
+
+    /// Get the lightness in \[0, 1] of an sRGB pixel with channels normalized to \[0, 1]
+    pub fn lightness_normalized(&self, rgb: [f32; 3]) -> f32 {
+        let y = normalized_srgb_to_luminance(rgb, self.linear_gamma, self.weights);
+        self.luminance_lightness(y)
+    }
+
+    /// Map luminance to lightness, through the CIE curve if `perceptual` is on
+    fn luminance_lightness(&self, y: f32) -> f32 {
+        if self.perceptual {
+            luminance_to_lightness(y) / 100.0
+        } else {
+            y
+        }
+    }
+
+    /// Get the lightness in \[0, 1] of an sRGBA pixel slice. The alpha channel is only used
+    /// when `alpha_relief` is on.
+    pub fn lightness_rgba(&self, pixel: &[u8]) -> f32 {
+        let l = self.lightness(&pixel[..3]);
+        self.finish(l, pixel[3] as f32 / 255.0)
+    }
+
+    /// Get the lightness in \[0, 1] of a 16-bit sRGBA pixel slice, keeping the full precision
+    /// of each channel
+    pub fn lightness_rgba16(&self, pixel: &[u16]) -> f32 {
+        let channel = |c: u16| c as f32 / 65535.0;
+        self.lightness_rgba_normalized([
+            channel(pixel[0]),
+            channel(pixel[1]),
+            channel(pixel[2]),
+            channel(pixel[3]),
+        ])
+    }
+
+    /// Get the lightness in \[0, 1] of an sRGBA pixel with every channel, alpha included,
+    /// normalized to \[0, 1]
+    pub fn lightness_rgba_normalized(&self, pixel: [f32; 4]) -> f32 {
+        let l = self.lightness_normalized([pixel[0], pixel[1], pixel[2]]);
+        self.finish(l, pixel[3])
+    }
+
+    /// Apply the alpha relief, inversion and alpha threshold steps to lightness `l` of a pixel
+    /// with opacity `alpha`
+    fn finish(&self, l: f32, alpha: f32) -> f32 {
+        if alpha < self.alpha_threshold {
+            return self.transparent_lightness;
+        }
+        let l = if self.alpha_relief {
+            1.0 - alpha * (1.0 - l)
+        } else {
+            l
+        };
+        if self.invert {
+            1.0 - l
+        } else {
+            l
+        }
+    }
+
+    /// Get the lightness of every pixel of an image, row by row. Images with more than 8 bits
+    /// per channel are read at 16 bits, so smooth gradients don't band.
+    pub fn lightnesses(&self, image: &image::DynamicImage) -> Vec<f32> {
+        use image::DynamicImage::*;
+
+        match image {
+            ImageLuma16(_) | ImageLumaA16(_) | ImageRgb16(_) | ImageRgba16(_) | ImageRgb32F(_)
+            | ImageRgba32F(_) => image
+                .to_rgba16()
+                .chunks_exact(4)
+                .map(|p| self.lightness_rgba16(p))
+                .collect(),
+            _ => image
+                .to_rgba8()
+                .chunks_exact(4)
+                .map(|p| self.lightness_rgba(p))
+                .collect(),
+        }
+    }
+}
+
+impl Default for LightnessConversion {
+    fn default() -> Self {
+        Self {
+            linear_gamma: 1.0,
+            alpha_relief: false,
+            perceptual: true,
+            invert: false,
+            alpha_threshold: 0.0,
+            weights: LuminanceWeights::REC709,
+            transparent_lightness: 1.0,
+        }
+    }
+}
+
+/// Load an image from disk, reporting why it couldn't be used.
+///
+/// The format is sniffed from the file's contents, falling back to its extension. A file whose
+/// contents don't look like any known image is reported as `UnsupportedFormat` even if the
+/// extension claims otherwise; one that does but fails to decode is `CorruptImage`.
+pub fn load_image<P: AsRef<std::path::Path>>(
+    path: P,
+) -> Result<image::DynamicImage, crate::error::LithError> {
+    use crate::error::LithError;
+
+    let path = path.as_ref();
+    #[cfg(feature = "raw")]
+    {
+        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        if let Some(extension) = extension {
+            if RAW_FORMATS.contains(&extension.as_str()) {
+                return decode_raw(path).map_err(|e| LithError::CorruptImage(e.to_string()));
+            }
+        }
+    }
+
+    let bytes = std::fs::read(path)?;
+    let sniffed = image::guess_format(&bytes).ok();
+    let format = sniffed
+        .or_else(|| image::ImageFormat::from_path(path).ok())
+        .ok_or(LithError::UnsupportedFormat)?;
+
+    let image = image::load_from_memory_with_format(&bytes, format).map_err(|e| {
+        match (sniffed, LithError::from(e)) {
+            (None, LithError::CorruptImage(_)) => LithError::UnsupportedFormat,
+            (_, e) => e,
+        }
+    })?;
+
+    if image.width() == 0 || image.height() == 0 {
+        return Err(LithError::EmptyImage);
+    }
+    Ok(image)
+}
+
+/// File extensions of the camera RAW formats that `decode_raw` understands
+#[cfg(feature = "raw")]
+pub static RAW_FORMATS: &[&str] = &["dng", "cr2", "nef", "arw", "orf", "rw2", "raf", "pef"];
+
+/// The D65 white point of sRGB in XYZ, with Y = 1
+#[cfg(feature = "raw")]
+const D65_WHITE: [f32; 3] = [0.95047, 1.0, 1.08883];
+
+/// Converts XYZ to linear sRGB
+#[cfg(feature = "raw")]
+const XYZ_TO_SRGB: [[f32; 3]; 3] = [
+    [3.240_454, -1.537_139, -0.498_531],
+    [-0.969_266, 1.876_011, 0.041_556],
+    [0.055_643, -0.204_026, 1.057_225],
+];
+
+/// Decode a camera RAW or DNG file into an sRGB image.
+///
+/// Bayer data is demosaiced by merging each 2x2 block of the sensor into one pixel, so the
+/// output is half the sensor resolution. That is plenty for lithophanes and avoids
+/// interpolation artifacts. White balance from the file is applied first, then the camera's
+/// color matrix takes the sensor colors to sRGB, so lightness is computed from real colors.
+#[cfg(feature = "raw")]
+pub fn decode_raw<P: AsRef<std::path::Path>>(
+    path: P,
+) -> Result<image::DynamicImage, rawloader::RawLoaderError> {
+    let raw = rawloader::decode_file(path)?;
+
+    let wb = raw
+        .wb_coeffs
+        .map(|c| if c.is_finite() && c > 0.0 { c } else { 1.0 });
+    // Normalize a sample of color c to [0, 1] and white balance it relative to green
+    let sample = |i: usize, c: usize| {
+        let v = match raw.data {
+            rawloader::RawImageData::Integer(ref data) => {
+                let (black, white) = (raw.blacklevels[c] as f32, raw.whitelevels[c] as f32);
+                (data[i] as f32 - black) / (white - black).max(1.0)
+            }
+            rawloader::RawImageData::Float(ref data) => data[i],
+        };
+        (v * wb[c] / wb[1]).clamp(0.0, 1.0)
+    };
+    // The normalized matrix takes white balanced camera colors to XYZ with a neutral at
+    // (1, 1, 1), which is moved to the white of sRGB. A file without a matrix has all zeros,
+    // and its colors are used as they are.
+    let cam_to_xyz = raw.cam_to_xyz_normalized();
+    let has_matrix = raw.xyz_to_cam.iter().flatten().any(|&v| v != 0.0);
+    let to_srgb = |[r, g, b]: [f32; 3]| {
+        if !has_matrix {
+            return [r, g, b];
+        }
+        // A fourth CFA color is a second green where the matrix has one
+        let cam = [r, g, b, g];
+        let xyz: [f32; 3] = [0, 1, 2]
+            .map(|i| (0..4).map(|j| cam_to_xyz[i][j] * cam[j]).sum::<f32>() * D65_WHITE[i]);
+        XYZ_TO_SRGB.map(|row| (0..3).map(|j| row[j] * xyz[j]).sum::<f32>().clamp(0.0, 1.0))
+    };
+    let encode = |rgb: [f32; 3]| {
+        image::Rgb(to_srgb(rgb).map(|l| (linear_to_srgb(l) * 65535.0).round() as u16))
+    };
+
+    let [top, right, bottom, left] = raw.crops;
+    let (width, height) = (
+        raw.width.saturating_sub(left + right),
+        raw.height.saturating_sub(top + bottom),
+    );
+
+    let image = if raw.cpp == 3 {
+        image::ImageBuffer::from_fn(width as u32, height as u32, |x, y| {
+            let i = ((y as usize + top) * raw.width + x as usize + left) * 3;
+            encode([sample(i, 0), sample(i + 1, 1), sample(i + 2, 2)])
+        })
+    } else {
+        image::ImageBuffer::from_fn(width as u32 / 2, height as u32 / 2, |x, y| {
+            let mut sums = [0.0; 3];
+            let mut counts = [0.0; 3];
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let (row, col) = (y as usize * 2 + dy + top, x as usize * 2 + dx + left);
+                // The fourth CFA color (emerald or a second green) is treated as green
+                let c = raw.cfa.color_at(row, col).min(2);
+                sums[c] += sample(row * raw.width + col, raw.cfa.color_at(row, col));
+                counts[c] += 1.0;
+            }
+            encode([0, 1, 2].map(|c| sums[c] / f32::max(counts[c], 1.0)))
+        })
+    };
+
+    Ok(image::DynamicImage::ImageRgb16(image))
+}
@@ -4,3 +4,7 @@ pub mod gen;
 pub mod geo;
 /// Module containing useful image processing utilities
 pub mod img;
+/// Module containing the library's error type
+pub mod error;
+/// Module containing high-level options for generating a lithophane in one call
+pub mod options;
@@ -1,12 +1,24 @@
 use color_eyre::eyre::Result;
-use egui::{ColorImage, TextureHandle, Ui, Vec2};
-use image::{imageops::FilterType, io::Reader as ImageReader, DynamicImage};
+use egui::{ColorImage, Rect, Sense, TextureHandle, Ui, Vec2};
+use image::{imageops::FilterType, DynamicImage};
+use lith::error::LithError;
 use lith::gen::{
     cylinder_mesh::CylinderMeshGenerator, filter_image::FilterImagePreprocessor,
-    flat_mesh::FlatMeshGenerator, standard_image::StandardImagePreprocessor, ImagePreprocessor,
-    LithophaneGenerator,
+    flat_mesh::FlatMeshGenerator, preview, standard_image::StandardImagePreprocessor,
+    ImagePreprocessor, LightMap, LithophaneGenerator,
+};
+use lith::geo::{CheckStatus, Mesh, PrintEstimate, Printability, Vec3};
+use lith::options::{Preset, PRESETS};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc, Mutex,
+    },
 };
-use std::{fmt::Display, path::PathBuf};
 
 use eframe::egui;
 
@@ -14,14 +26,40 @@ struct App {
     path: Option<PathBuf>,
     display_image: Option<TextureHandle>,
     dyn_image: Option<DynamicImage>,
-    res: Option<Result<usize, &'static str>>,
+    res: Option<Result<usize, String>>,
     processor: Processor,
     generator: Generator,
+    preview: Preview,
+    azimuth: f32,
+    elevation: f32,
+    floor: f32,
+    ceiling: f32,
+    preview_image: Option<TextureHandle>,
+    zoom: f32,
+    pan: Vec2,
+    printability: Option<Printability>,
+    preset: Option<&'static Preset>,
+    compress: bool,
+    layer_height: f32,
+    print_estimate: Option<PrintEstimate>,
+    dimensions: Option<Vec3>,
+    preview_scale: usize,
+    job: Option<Job>,
+}
+
+/// A lithophane being generated on a background thread, so the window stays responsive
+struct Job {
+    /// Fraction of the mesh generated so far
+    progress: Arc<Mutex<f32>>,
+    result: Receiver<Result<Mesh, String>>,
+    output: PathBuf,
+    /// Whether `output` was named for a compressed file
+    compress: bool,
 }
 
 impl App {
-    fn try_load_image(&mut self, path: PathBuf, ui: &Ui) -> Result<()> {
-        let raw_image = ImageReader::open(&path)?.decode()?;
+    fn try_load_image(&mut self, path: PathBuf, ui: &Ui) -> Result<(), LithError> {
+        let raw_image = lith::img::load_image(&path)?;
         self.path = Some(path);
         let image = ColorImage::from_rgba_unmultiplied(
             [raw_image.width() as usize, raw_image.height() as usize],
@@ -29,39 +67,274 @@ impl App {
         );
         self.display_image = Some(ui.ctx().load_texture("image", image, Default::default()));
         self.dyn_image = Some(raw_image);
+        self.zoom = 1.0;
+        self.pan = Vec2::splat(0.5);
 
         Ok(())
     }
 
-    fn generate_lithophane(&mut self) {
-        let map = match self.processor {
+    fn light_map(&self, image: &DynamicImage) -> LightMap {
+        match self.processor {
             Processor::Standard(width) => StandardImagePreprocessor::default()
                 .width(width)
-                .transform(self.dyn_image.as_ref().unwrap()),
+                .transform(image),
             Processor::Filter(width, filter) => FilterImagePreprocessor::default()
                 .width(width)
                 .filter(FILTER_TYPES[filter])
-                .transform(self.dyn_image.as_ref().unwrap()),
+                .transform(image),
+        }
+    }
+
+    /// Apply the floor and ceiling to `map`, clamping the lightness outside them
+    fn leveled(&self, mut map: LightMap) -> LightMap {
+        map.levels(self.floor, self.ceiling);
+        map
+    }
+
+    /// Estimate the triangle count of the lithophane for the current image and settings
+    fn estimated_triangles(&self) -> Option<usize> {
+        let image = self.dyn_image.as_ref()?;
+        let (w, h) = (image.width(), image.height());
+        if let Some(preset) = self.preset {
+            let dims = StandardImagePreprocessor::default()
+                .width(preset.resolution)
+                .output_dims(w, h);
+            return Some(FlatMeshGenerator::default().estimated_triangles(dims));
+        }
+        let dims = match self.processor {
+            Processor::Standard(width) => StandardImagePreprocessor::default()
+                .width(width)
+                .output_dims(w, h),
+            Processor::Filter(width, _) => FilterImagePreprocessor::default()
+                .width(width)
+                .output_dims(w, h),
         };
-        let mesh = match self.generator {
-            Generator::FlatMesh(scaling) => {
-                FlatMeshGenerator::default().scaling(scaling).generate(map)
-            }
-            Generator::Cylinder(scaling, radius, height) => CylinderMeshGenerator::default()
-                .scaling(scaling)
+        Some(match self.generator {
+            Generator::FlatMesh(_) => FlatMeshGenerator::default().estimated_triangles(dims),
+            Generator::Cylinder { radius, height, .. } => CylinderMeshGenerator::default()
                 .radius(radius)
                 .height(height)
-                .generate(map),
+                .estimated_triangles(dims),
+        })
+    }
+
+    fn update_preview(&mut self, ui: &Ui) {
+        self.preview_image = None;
+        let Some(ref image) = self.dyn_image else {
+            return;
         };
 
-        let r = std::fs::write(
-            self.path.as_ref().unwrap().with_extension("stl"),
-            mesh.as_stl_bytes(),
+        let Some(rendered) = self.render_preview(&self.light_map(image), 1) else {
+            return;
+        };
+        let image = ColorImage::from_rgba_unmultiplied(
+            [rendered.width() as usize, rendered.height() as usize],
+            &rendered.to_rgba8(),
         );
+        self.preview_image = Some(ui.ctx().load_texture("preview", image, Default::default()));
+    }
+
+    /// Render the selected preview `factor` times larger than the light map
+    fn render_preview(&self, map: &LightMap, factor: usize) -> Option<DynamicImage> {
+        Some(match self.preview {
+            Preview::Source => return None,
+            Preview::Hillshade => preview::hillshade_supersampled(
+                &self.leveled(map.clone()),
+                self.generator.scaling(),
+                self.azimuth,
+                self.elevation,
+                factor,
+            ),
+            Preview::Heightmap => {
+                preview::heightmap(&map.upscale(factor), self.floor, self.ceiling)
+            }
+        })
+    }
+
+    /// Save the selected preview at `preview_scale` times the light map's resolution
+    fn export_preview(&mut self, output: PathBuf) {
+        let Some(ref image) = self.dyn_image else {
+            return;
+        };
+        let Some(rendered) = self.render_preview(&self.light_map(image), self.preview_scale) else {
+            return;
+        };
+        if let Err(err) = rendered.save(output) {
+            self.res = Some(Err(format!("Couldn't save the preview: {err}")));
+        }
+    }
+
+    /// Show the source image and the preview side by side. Both share one zoom (scroll) and
+    /// pan (drag) so the same region is always visible in each.
+    fn show_images(&mut self, ui: &mut Ui) {
+        let textures: Vec<TextureHandle> = [&self.display_image, &self.preview_image]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let Some(first) = textures.first() else {
+            return;
+        };
+
+        let s = first.size();
+        let mut h = ui.available_height() - 80.0;
+        let mut w = s[0] as f32 * h / s[1] as f32;
+        let max_w = ui.available_width() / textures.len() as f32 - ui.spacing().item_spacing.x;
+        if w > max_w {
+            h *= max_w / w;
+            w = max_w;
+        }
+
+        let half = 0.5 / self.zoom;
+        let center = self.pan.clamp(Vec2::splat(half), Vec2::splat(1.0 - half));
+        let uv = Rect::from_center_size(center.to_pos2(), Vec2::splat(2.0 * half));
+
+        ui.horizontal(|ui| {
+            let used = (w + ui.spacing().item_spacing.x) * textures.len() as f32;
+            ui.add_space(((ui.available_width() - used) / 2.0).max(0.0));
+            for texture in &textures {
+                let response = ui.add(
+                    egui::Image::new((texture.id(), Vec2 { x: w, y: h }))
+                        .uv(uv)
+                        .sense(Sense::drag()),
+                );
+                if response.hovered() {
+                    let scroll = ui.input(|i| i.scroll_delta.y);
+                    self.zoom = (self.zoom * (scroll * 0.005).exp()).clamp(1.0, 32.0);
+                }
+                if response.dragged() {
+                    let delta = response.drag_delta();
+                    self.pan = center - Vec2::new(delta.x / w, delta.y / h) / self.zoom;
+                }
+            }
+        });
+
+        let half = 0.5 / self.zoom;
+        self.pan = self.pan.clamp(Vec2::splat(half), Vec2::splat(1.0 - half));
+    }
+
+    /// Get where the lithophane is saved, if both the image and the path it was loaded from
+    /// are available
+    fn output_path(&self) -> Option<PathBuf> {
+        lith::options::output_path(self.dyn_image.as_ref(), self.path.as_deref(), self.compress)
+    }
+
+    /// Whether both the image and the path it was loaded from are available
+    fn can_generate(&self) -> bool {
+        self.output_path().is_some()
+    }
+
+    fn generate_lithophane(&mut self, ctx: &egui::Context) {
+        let (Some(image), Some(output)) = (&self.dyn_image, self.output_path()) else {
+            self.res = Some(Err(
+                "Please select an image before generating a lithophane".into()
+            ));
+            return;
+        };
+
+        if let Some(preset) = self.preset {
+            let image = image.clone();
+            self.spawn_job(ctx, output, move |_| {
+                preset.options().generate(&image).map_err(|e| e.to_string())
+            });
+            return;
+        }
+
+        let map = self.leveled(self.light_map(image));
+        match self.generator {
+            Generator::FlatMesh(scaling) => {
+                let generator = FlatMeshGenerator::default().scaling(scaling);
+                self.spawn_job(ctx, output, move |progress| {
+                    generator
+                        .generate_with_progress(map, progress)
+                        .map_err(|e| e.to_string())
+                });
+            }
+            Generator::Cylinder {
+                scaling,
+                radius,
+                height,
+            } => {
+                let generator = CylinderMeshGenerator::default()
+                    .scaling(scaling)
+                    .radius(radius)
+                    .height(height);
+                self.spawn_job(ctx, output, move |progress| {
+                    generator
+                        .generate_with_progress(map, progress)
+                        .map_err(|e| e.to_string())
+                });
+            }
+        }
+    }
+
+    /// Run `work` on a background thread, repainting as it reports progress
+    fn spawn_job(
+        &mut self,
+        ctx: &egui::Context,
+        output: PathBuf,
+        work: impl FnOnce(&mut dyn FnMut(f32)) -> Result<Mesh, String> + Send + 'static,
+    ) {
+        let progress = Arc::new(Mutex::new(0.0));
+        let (sender, result) = mpsc::channel();
+        let (shared, ctx) = (progress.clone(), ctx.clone());
+        std::thread::spawn(move || {
+            let mesh = work(&mut |fraction| {
+                *shared.lock().unwrap() = fraction;
+                ctx.request_repaint();
+            });
+            // The window may have been closed while generating
+            let _ = sender.send(mesh);
+            ctx.request_repaint();
+        });
+        self.job = Some(Job {
+            progress,
+            result,
+            output,
+            compress: self.compress,
+        });
+    }
+
+    /// Save the mesh once the background generation finishes
+    fn poll_job(&mut self) {
+        let Some(ref job) = self.job else {
+            return;
+        };
+        match job.result.try_recv() {
+            Ok(Ok(mesh)) => {
+                let job = self.job.take().unwrap();
+                self.save_mesh(mesh, job.output, job.compress);
+            }
+            Ok(Err(e)) => {
+                self.job = None;
+                self.res = Some(Err(e));
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Disconnected) => {
+                self.job = None;
+                self.res = Some(Err("Generation stopped unexpectedly".into()));
+            }
+        }
+    }
+
+    fn save_mesh(&mut self, mesh: Mesh, output: PathBuf, compress: bool) {
+        self.printability = Some(mesh.printability(MIN_WALL));
+        self.print_estimate = Some(mesh.print_estimate(self.layer_height));
+        self.dimensions = Some(mesh.dimensions());
+
+        let r = File::create(output).and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            if compress {
+                mesh.write_stl_gz(&mut writer)?;
+            } else {
+                mesh.write_stl(&mut writer)?;
+            }
+            writer.flush()
+        });
 
         if let Err(err) = r {
             println!("{:?}", err);
-            self.res = Some(Err("Please check the console for more information..."));
+            self.res = Some(Err(format!("Couldn't save the lithophane: {err}")));
         } else {
             self.res = Some(Ok(10));
         }
@@ -77,11 +350,42 @@ impl Default for App {
             res: None,
             processor: Processor::Standard(80),
             generator: Generator::FlatMesh(2.0),
+            preview: Preview::Source,
+            azimuth: 315.0,
+            elevation: 45.0,
+            floor: 0.0,
+            ceiling: 1.0,
+            preview_image: None,
+            zoom: 1.0,
+            pan: Vec2::splat(0.5),
+            printability: None,
+            preset: None,
+            compress: false,
+            layer_height: 0.12,
+            print_estimate: None,
+            dimensions: None,
+            preview_scale: 4,
+            job: None,
         }
     }
 }
 
+/// Memory used by one triangle of the generated mesh: three vertices of three f32s
+const TRIANGLE_BYTES: usize = 36;
+/// Triangle count above which the size estimate is shown as a warning
+const TRIANGLE_WARNING: usize = 4_000_000;
+/// Print time above which the print estimate is shown as a warning, in seconds
+const PRINT_TIME_WARNING: f32 = 12.0 * 60.0 * 60.0;
+/// Thinnest wall that prints reliably with a typical 0.4 mm nozzle, in mm
+const MIN_WALL: f32 = 0.8;
+
+#[cfg(not(feature = "raw"))]
 static FILE_FORMATS: &[&str] = &["png", "jpg", "jpeg", "bmp", "qoi", "tiff"];
+#[cfg(feature = "raw")]
+static FILE_FORMATS: &[&str] = &[
+    "png", "jpg", "jpeg", "bmp", "qoi", "tiff", "dng", "cr2", "nef", "arw", "orf", "rw2", "raf",
+    "pef",
+];
 static FILTER_TYPES: &[FilterType] = &[
     FilterType::Nearest,
     FilterType::Triangle,
@@ -117,7 +421,20 @@ impl Display for Processor {
 
 enum Generator {
     FlatMesh(f32),
-    Cylinder(f32, f32, f32),
+    Cylinder {
+        scaling: f32,
+        radius: f32,
+        height: f32,
+    },
+}
+
+impl Generator {
+    fn scaling(&self) -> f32 {
+        match self {
+            Generator::FlatMesh(scaling) => *scaling,
+            Generator::Cylinder { scaling, .. } => *scaling,
+        }
+    }
 }
 
 impl Display for Generator {
@@ -127,7 +444,28 @@ impl Display for Generator {
             "{}",
             match self {
                 Generator::FlatMesh(_) => "Flat Mesh",
-                Generator::Cylinder(_, _, _) => "Cylindrical",
+                Generator::Cylinder { .. } => "Cylinder",
+            }
+        )
+    }
+}
+
+#[derive(PartialEq)]
+enum Preview {
+    Source,
+    Hillshade,
+    Heightmap,
+}
+
+impl Display for Preview {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Preview::Source => "Source Image",
+                Preview::Hillshade => "Hillshade",
+                Preview::Heightmap => "Heightmap",
             }
         )
     }
@@ -135,23 +473,26 @@ impl Display for Generator {
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _: &mut eframe::Frame) {
+        self.poll_job();
         egui::CentralPanel::default().show(ctx, |ui| {
+            let mut settings_changed = false;
+
             ui.horizontal(|ui| {
                 if ui.button("Select image...").clicked() {
                     self.display_image = None;
+                    self.dyn_image = None;
                     self.path = None;
                     let path = rfd::FileDialog::new()
-                        .add_filter("Image Files", &FILE_FORMATS)
+                        .add_filter("Image Files", FILE_FORMATS)
                         .add_filter("All Files", &[""])
                         .pick_file();
                     if let Some(p) = path {
-                        let err = self.try_load_image(p, ui);
-                        if let Err(e) = err {
-                            println!("{:?}", e);
-                            self.res =
-                                Some(Err("Please check the console for more information..."));
+                        self.res = None;
+                        if let Err(e) = self.try_load_image(p, ui) {
+                            self.res = Some(Err(load_error_message(&e)));
                         }
                     }
+                    settings_changed = true;
                 }
 
                 match &self.path {
@@ -163,30 +504,39 @@ impl eframe::App for App {
             ui.menu_button(format!("Image Processor: {}", self.processor), |ui| {
                 if ui.button("Standard").clicked() {
                     self.processor = Processor::Standard(80);
+                    settings_changed = true;
                     ui.close_menu();
                 }
                 if ui.button("Filtered").clicked() {
                     self.processor = Processor::Filter(80, 3);
+                    settings_changed = true;
                     ui.close_menu();
                 }
             });
 
             match self.processor {
                 Processor::Standard(ref mut width) => {
-                    ui.horizontal(|ui| {
-                        ui.label("Width");
-                        ui.add(egui::Slider::new(width, 20..=720))
-                    });
+                    settings_changed |= ui
+                        .horizontal(|ui| {
+                            ui.label("Width");
+                            ui.add(egui::Slider::new(width, 20..=720))
+                        })
+                        .inner
+                        .changed();
                 }
                 Processor::Filter(ref mut width, ref mut filter) => {
-                    ui.horizontal(|ui| {
-                        ui.label("Width");
-                        ui.add(egui::Slider::new(width, 20..=720))
-                    });
+                    settings_changed |= ui
+                        .horizontal(|ui| {
+                            ui.label("Width");
+                            ui.add(egui::Slider::new(width, 20..=720))
+                        })
+                        .inner
+                        .changed();
                     ui.menu_button(format!("Filter: {}", FILTER_NAMES[*filter]), |ui| {
                         for (i, name) in FILTER_NAMES.iter().enumerate() {
                             if ui.button(*name).clicked() {
                                 *filter = i;
+                                settings_changed = true;
                                 ui.close_menu();
                             }
                         }
@@ -195,28 +545,46 @@ impl eframe::App for App {
             }
 
             ui.menu_button(format!("Mesh Generator: {}", self.generator), |ui| {
+                // Scaling is the relief depth for every generator, so it carries over
+                let scaling = self.generator.scaling();
                 if ui.button("Flat Mesh").clicked() {
-                    self.generator = Generator::FlatMesh(2.0);
+                    self.generator = Generator::FlatMesh(scaling);
+                    settings_changed = true;
                     ui.close_menu();
                 }
-                if ui.button("Cylindrical").clicked() {
-                    self.generator = Generator::Cylinder(2.0, 20.0, 20.0);
+                if ui.button("Cylinder").clicked() {
+                    self.generator = Generator::Cylinder {
+                        scaling,
+                        radius: 20.0,
+                        height: 20.0,
+                    };
+                    settings_changed = true;
                     ui.close_menu();
                 }
             });
 
             match self.generator {
                 Generator::FlatMesh(ref mut scaling) => {
-                    ui.horizontal(|ui| {
-                        ui.label("Scaling");
-                        ui.add(egui::Slider::new(scaling, 0.0..=5.0))
-                    });
+                    settings_changed |= ui
+                        .horizontal(|ui| {
+                            ui.label("Scaling");
+                            ui.add(egui::Slider::new(scaling, 0.0..=5.0))
+                        })
+                        .inner
+                        .changed();
                 }
-                Generator::Cylinder(ref mut scaling, ref mut radius, ref mut height) => {
-                    ui.horizontal(|ui| {
-                        ui.label("Scaling");
-                        ui.add(egui::Slider::new(scaling, 0.0..=5.0))
-                    });
+                Generator::Cylinder {
+                    ref mut scaling,
+                    ref mut radius,
+                    ref mut height,
+                } => {
+                    settings_changed |= ui
+                        .horizontal(|ui| {
+                            ui.label("Scaling");
+                            ui.add(egui::Slider::new(scaling, 0.0..=5.0))
+                        })
+                        .inner
+                        .changed();
                     ui.horizontal(|ui| {
                         ui.label("Radius");
                         ui.add(egui::Slider::new(radius, 0.0..=50.0))
@@ -228,18 +596,113 @@ impl eframe::App for App {
                 }
             }
 
-            if let Some(ref texture) = self.display_image {
-                let s = texture.size();
-                let h = ui.available_height() - 80.0;
-                let w = s[0] as f32 * h / s[1] as f32;
-                ui.vertical_centered(|ui| ui.image((texture.id(), Vec2 { x: w, y: h })));
+            // Lightness outside these is clamped when generating, which the heightmap shows
+            settings_changed |= ui
+                .horizontal(|ui| {
+                    ui.label("Floor");
+                    let floor = ui.add(egui::Slider::new(&mut self.floor, 0.0..=1.0));
+                    ui.label("Ceiling");
+                    floor | ui.add(egui::Slider::new(&mut self.ceiling, 0.0..=1.0))
+                })
+                .inner
+                .changed();
+
+            ui.menu_button(
+                format!("Size Preset: {}", self.preset.map_or("None", |p| p.name)),
+                |ui| {
+                    if ui.button("None").clicked() {
+                        self.preset = None;
+                        ui.close_menu();
+                    }
+                    for preset in PRESETS {
+                        if ui.button(preset.name).clicked() {
+                            self.preset = Some(preset);
+                            ui.close_menu();
+                        }
+                    }
+                },
+            );
+            if let Some(preset) = self.preset {
+                ui.label(format!(
+                    "Overrides the settings above: {}mm wide, {}mm deep, {} samples",
+                    preset.width_mm, preset.relief_depth_mm, preset.resolution
+                ));
             }
 
-            if !self.dyn_image.is_none() {
+            if let Some(triangles) = self.estimated_triangles() {
+                let megabytes = (triangles * TRIANGLE_BYTES) as f32 / 1_000_000.0;
+                let text = format!("Estimated size: {triangles} triangles, ~{megabytes:.1} MB");
+                if triangles > TRIANGLE_WARNING {
+                    ui.colored_label(egui::Color32::RED, text);
+                } else {
+                    ui.label(text);
+                }
+            }
+
+            ui.horizontal(|ui| {
+                ui.menu_button(format!("Preview: {}", self.preview), |ui| {
+                    for preview in [Preview::Source, Preview::Hillshade, Preview::Heightmap] {
+                        if ui.button(preview.to_string()).clicked() {
+                            self.preview = preview;
+                            settings_changed = true;
+                            ui.close_menu();
+                        }
+                    }
+                });
+                match self.preview {
+                    Preview::Source | Preview::Heightmap => {}
+                    Preview::Hillshade => {
+                        ui.label("Azimuth");
+                        settings_changed |= ui
+                            .add(egui::Slider::new(&mut self.azimuth, 0.0..=360.0))
+                            .changed();
+                        ui.label("Elevation");
+                        settings_changed |= ui
+                            .add(egui::Slider::new(&mut self.elevation, 0.0..=90.0))
+                            .changed();
+                    }
+                }
+                if self.preview != Preview::Source && self.dyn_image.is_some() {
+                    ui.label("Export scale");
+                    ui.add(egui::Slider::new(&mut self.preview_scale, 1..=8));
+                    if ui.button("Export preview...").clicked() {
+                        let path = rfd::FileDialog::new()
+                            .add_filter("PNG Image", &["png"])
+                            .set_file_name("preview.png")
+                            .save_file();
+                        if let Some(p) = path {
+                            self.export_preview(p);
+                        }
+                    }
+                }
+            });
+
+            if settings_changed {
+                self.update_preview(ui);
+            }
+
+            self.show_images(ui);
+
+            if self.dyn_image.is_some() {
                 ui.vertical_centered(|ui| {
-                    if ui.button("Generate Lithophane").clicked() {
-                        self.res = None;
-                        self.generate_lithophane();
+                    ui.checkbox(&mut self.compress, "Compress output (.stl.gz)");
+                    ui.horizontal(|ui| {
+                        ui.label("Layer height (mm)");
+                        ui.add(egui::Slider::new(&mut self.layer_height, 0.04..=0.32));
+                    });
+                    if let Some(ref job) = self.job {
+                        let fraction = *job.progress.lock().unwrap();
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .show_percentage()
+                                .animate(true),
+                        );
+                    } else {
+                        let button = egui::Button::new("Generate Lithophane");
+                        if ui.add_enabled(self.can_generate(), button).clicked() {
+                            self.res = None;
+                            self.generate_lithophane(ui.ctx());
+                        }
                     }
                 });
             }
@@ -252,10 +715,27 @@ impl eframe::App for App {
                             ui.ctx().request_repaint();
                         } else {
                             ui.label("Lithophane successfully generated...");
+                            if let Some(ref size) = self.dimensions {
+                                ui.label(format!(
+                                    "Size: {:.1} × {:.1} × {:.1} mm",
+                                    size.x, size.y, size.z
+                                ));
+                            }
+                            if let Some(estimate) = self.print_estimate {
+                                let text = format!("Estimated print time: {estimate}");
+                                if estimate.seconds > PRINT_TIME_WARNING {
+                                    ui.colored_label(egui::Color32::RED, text);
+                                } else {
+                                    ui.label(text);
+                                }
+                            }
+                            if let Some(ref printability) = self.printability {
+                                show_printability(ui, printability);
+                            }
                         }
                     }
                     Err(msg) => {
-                        ui.label(&format!("ERROR: {msg}"));
+                        ui.label(format!("ERROR: {msg}"));
                     }
                 };
             }
@@ -263,6 +743,40 @@ impl eframe::App for App {
     }
 }
 
+/// Show the overall printability of the last mesh, followed by each check on its own line
+fn show_printability(ui: &mut Ui, printability: &Printability) {
+    let color = |status| match status {
+        CheckStatus::Pass => egui::Color32::GREEN,
+        CheckStatus::Warn => egui::Color32::YELLOW,
+        CheckStatus::Fail => egui::Color32::RED,
+    };
+    ui.colored_label(
+        color(printability.status()),
+        format!("Printability: {}", printability.status()),
+    );
+    ui.indent("printability", |ui| {
+        for check in &printability.checks {
+            ui.colored_label(color(check.status), check.to_string());
+        }
+    });
+}
+
+/// Describe an image loading failure in terms a user can act on
+fn load_error_message(err: &LithError) -> String {
+    match err {
+        LithError::UnsupportedFormat => {
+            "That file isn't a supported image. Try a PNG, JPEG, BMP, QOI or TIFF.".into()
+        }
+        LithError::CorruptImage(_) => {
+            "That image appears to be damaged or incomplete. Try re-saving or re-downloading it."
+                .into()
+        }
+        LithError::EmptyImage => "That image has no pixels.".into(),
+        LithError::Io(e) => format!("Couldn't read the file: {e}"),
+        e => e.to_string(),
+    }
+}
+
 fn main() -> Result<()> {
     color_eyre::install()?;
 
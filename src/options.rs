@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+
+use image::DynamicImage;
+
+use crate::{
+    error::LithError,
+    gen::{
+        flat_mesh::FlatMeshGenerator, standard_image::StandardImagePreprocessor, ImagePreprocessor,
+        LithophaneGenerator,
+    },
+    geo::Mesh,
+};
+
+/// How far the requested height may be from the image's aspect ratio before it is an error
+const ASPECT_TOLERANCE: f32 = 0.02;
+
+/// Where a slicer expects a model to be placed so it lands in the middle of the bed on import.
+///
+/// Every preset keeps the relief facing +Z and rests the back of the lithophane on z = 0,
+/// since all of these slicers treat +Z as up. They differ in where the bed origin is, so the
+/// footprint is centered on each slicer's default bed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlicerPreset {
+    /// Original Prusa printers, with the origin in the front-left corner of a 250x210mm bed
+    PrusaSlicer,
+    /// Ultimaker Cura, which puts the origin in the center of the bed
+    Cura,
+    /// Bambu Studio, with the origin in the front-left corner of a 256x256mm bed
+    Bambu,
+}
+
+impl SlicerPreset {
+    /// Get the (x, y) position of the center of the bed in this slicer's coordinates
+    pub fn bed_center(self) -> (f32, f32) {
+        match self {
+            SlicerPreset::PrusaSlicer => (125.0, 105.0),
+            SlicerPreset::Cura => (0.0, 0.0),
+            SlicerPreset::Bambu => (128.0, 128.0),
+        }
+    }
+
+    /// Move `mesh` so it rests on z = 0 with its footprint centered on the bed
+    fn place(self, mesh: &mut Mesh) {
+        let (min, max) = mesh.bounds();
+        let (cx, cy) = self.bed_center();
+        let dx = cx - (min.x + max.x) / 2.0;
+        let dy = cy - (min.y + max.y) / 2.0;
+        mesh.map_vertices(|v| {
+            v.x += dx;
+            v.y += dy;
+            v.z -= min.z;
+        });
+    }
+}
+
+/// Named dimensions for a common product, applied to `GenerationOptions` all at once
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Preset {
+    /// Name shown to the user and used to look the preset up
+    pub name: &'static str,
+    /// Number of samples across the width of the image
+    pub resolution: usize,
+    /// Width of the finished piece in millimeters
+    pub width_mm: f32,
+    /// Height of the finished piece in millimeters, if the product needs an exact height.
+    /// Images of a different aspect ratio are rejected when this is set.
+    pub height_mm: Option<f32>,
+    /// Thickness of the relief in millimeters
+    pub relief_depth_mm: f32,
+}
+
+/// Every built-in preset
+pub static PRESETS: &[Preset] = &[
+    Preset {
+        name: "7x9 cm frame insert",
+        resolution: 140,
+        width_mm: 70.0,
+        height_mm: Some(90.0),
+        relief_depth_mm: 3.0,
+    },
+    Preset {
+        name: "10x15 cm frame insert",
+        resolution: 200,
+        width_mm: 100.0,
+        height_mm: Some(150.0),
+        relief_depth_mm: 3.0,
+    },
+    Preset {
+        name: "13x18 cm frame insert",
+        resolution: 260,
+        width_mm: 130.0,
+        height_mm: Some(180.0),
+        relief_depth_mm: 3.0,
+    },
+    Preset {
+        name: "Tea-light wrap",
+        resolution: 250,
+        width_mm: 126.0,
+        height_mm: None,
+        relief_depth_mm: 2.2,
+    },
+    Preset {
+        name: "Night light panel",
+        resolution: 160,
+        width_mm: 80.0,
+        height_mm: None,
+        relief_depth_mm: 2.5,
+    },
+];
+
+impl Preset {
+    /// Look up a built-in preset by name, ignoring case
+    pub fn find(name: &str) -> Option<&'static Preset> {
+        PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get options with this preset's dimensions. Builder calls on the result override the
+    /// preset.
+    pub fn options(&self) -> GenerationOptions {
+        GenerationOptions {
+            resolution: self.resolution,
+            physical_width_mm: self.width_mm,
+            physical_height_mm: self.height_mm,
+            relief_depth_mm: self.relief_depth_mm,
+            ..Default::default()
+        }
+    }
+}
+
+/// Options for turning an image straight into a flat lithophane of a given physical size.
+///
+/// The pixel pitch and relief scaling are computed from the requested dimensions, so the
+/// output comes out at exactly `physical_width_mm` wide (and `physical_height_mm` tall, if
+/// set), with pure black pixels `relief_depth_mm` thick.
+pub struct GenerationOptions {
+    resolution: usize,
+    physical_width_mm: f32,
+    physical_height_mm: Option<f32>,
+    relief_depth_mm: f32,
+    slicer_preset: Option<SlicerPreset>,
+}
+
+impl GenerationOptions {
+    /// Set the number of samples taken across the width of the image
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Set the width of the finished lithophane in millimeters
+    pub fn physical_width_mm(mut self, mm: f32) -> Self {
+        self.physical_width_mm = mm;
+        self
+    }
+
+    /// Set the height of the finished lithophane in millimeters. It must match the image's
+    /// aspect ratio to within 2%; when unset it is derived from the width.
+    pub fn physical_height_mm(mut self, mm: f32) -> Self {
+        self.physical_height_mm = Some(mm);
+        self
+    }
+
+    /// Set the thickness of the relief in millimeters
+    pub fn relief_depth_mm(mut self, mm: f32) -> Self {
+        self.relief_depth_mm = mm;
+        self
+    }
+
+    /// Place the output where `preset` expects it, centered on the bed and resting on it. By
+    /// default the mesh keeps the generator's coordinates.
+    pub fn slicer_preset(mut self, preset: SlicerPreset) -> Self {
+        self.slicer_preset = Some(preset);
+        self
+    }
+
+    /// Generate a flat lithophane from `image` using these options
+    pub fn generate(&self, image: &DynamicImage) -> Result<Mesh, LithError> {
+        if self.resolution < 2 {
+            return Err(LithError::InvalidOptions("resolution must be at least 2"));
+        }
+        if self.physical_width_mm <= 0.0 || self.relief_depth_mm <= 0.0 {
+            return Err(LithError::InvalidOptions("dimensions must be positive"));
+        }
+        if image.width() == 0 || image.height() == 0 {
+            return Err(LithError::InvalidOptions("image is empty"));
+        }
+
+        let aspect_height = self.physical_width_mm * image.height() as f32 / image.width() as f32;
+        if let Some(requested) = self.physical_height_mm {
+            if (requested - aspect_height).abs() > aspect_height * ASPECT_TOLERANCE {
+                return Err(LithError::AspectMismatch {
+                    expected_mm: aspect_height,
+                    requested_mm: requested,
+                });
+            }
+        }
+
+        let map = StandardImagePreprocessor::default()
+            .width(self.resolution)
+            .transform(image);
+        let (width, height) = map.dims();
+        if width < 2 || height < 2 {
+            return Err(LithError::InvalidOptions(
+                "image is too small for the resolution",
+            ));
+        }
+
+        let pitch = self.physical_width_mm / (width - 1) as f32;
+        let mut mesh = FlatMeshGenerator::default()
+            .scaling(self.relief_depth_mm)
+            .pixel_size(pitch)
+            .generate(map)?;
+
+        // Rounding the sample grid can leave the height slightly off, so stretch it to match
+        let target = self.physical_height_mm.unwrap_or(aspect_height);
+        let stretch = target / ((height - 1) as f32 * pitch);
+        mesh.map_vertices(|v| v.y *= stretch);
+
+        if let Some(preset) = self.slicer_preset {
+            preset.place(&mut mesh);
+        }
+
+        Ok(mesh)
+    }
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Self {
+            resolution: 80,
+            physical_width_mm: 80.0,
+            physical_height_mm: None,
+            relief_depth_mm: 2.0,
+            slicer_preset: None,
+        }
+    }
+}
+
+/// Get where the lithophane of an image loaded from `source` is saved: next to the image, with
+/// an .stl extension, or .stl.gz when `compress` is set. Generating needs both the decoded
+/// image and the path it came from, so this is `None` unless both are present.
+pub fn output_path(
+    image: Option<&DynamicImage>,
+    source: Option<&Path>,
+    compress: bool,
+) -> Option<PathBuf> {
+    image?;
+    Some(source?.with_extension(if compress { "stl.gz" } else { "stl" }))
+}
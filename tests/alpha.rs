@@ -0,0 +1,100 @@
+//! Tests that transparent pixels can be dropped to the base of the lithophane.
+
+mod common;
+
+use image::{DynamicImage, RgbaImage};
+use lith::gen::{
+    flat_mesh::FlatMeshGenerator, standard_image::StandardImagePreprocessor, ImagePreprocessor,
+    LithophaneGenerator,
+};
+
+/// A black image whose left half is fully transparent and right half opaque
+fn half_transparent() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(8, 4, |x, _| {
+        image::Rgba([0, 0, 0, if x < 4 { 0 } else { 255 }])
+    }))
+}
+
+#[test]
+fn transparent_pixels_land_at_the_base() {
+    let map = StandardImagePreprocessor::default()
+        .width(8)
+        .alpha_threshold(0.5)
+        .transform(&half_transparent());
+    for (i, &l) in map.lightnesses().iter().enumerate() {
+        let expected = if i % 8 < 4 { 1.0 } else { 0.0 };
+        assert_eq!(l, expected, "pixel {i}");
+    }
+
+    // The transparent half has no relief over the back of the lithophane
+    let generator = FlatMeshGenerator::default().scaling(2.0);
+    let (base, _) = generator.relief_range();
+    let obj = generator.generate(map).unwrap().as_obj();
+    for line in obj.lines().filter_map(|l| l.strip_prefix("v ")) {
+        let v: Vec<f32> = line.split(' ').map(|c| c.parse().unwrap()).collect();
+        if v[0] < 3.0 {
+            assert_eq!(v[2], base, "vertex {line}");
+        }
+    }
+}
+
+#[test]
+fn threshold_applies_after_invert() {
+    let map = StandardImagePreprocessor::default()
+        .width(8)
+        .invert(true)
+        .alpha_threshold(0.5)
+        .transform(&half_transparent());
+    // Inverted black is white, and transparent pixels stay at the base either way
+    assert!(map.lightnesses().iter().all(|&l| l == 1.0));
+}
+
+#[test]
+fn no_threshold_keeps_transparent_pixels() {
+    let map = StandardImagePreprocessor::default()
+        .width(8)
+        .transform(&half_transparent());
+    assert!(map.lightnesses().iter().all(|&l| l == 0.0));
+}
+
+#[test]
+fn transparent_lightness_is_configurable() {
+    let map = StandardImagePreprocessor::default()
+        .width(8)
+        .alpha_threshold(0.5)
+        .transparent_lightness(0.25)
+        .transform(&half_transparent());
+    for (i, &l) in map.lightnesses().iter().enumerate() {
+        let expected = if i % 8 < 4 { 0.25 } else { 0.0 };
+        assert_eq!(l, expected, "pixel {i}");
+    }
+}
+
+#[test]
+fn alpha_relief_thins_semi_transparent_pixels() {
+    // Black everywhere, half opaque on the left and fully opaque on the right
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(8, 4, |x, _| {
+        image::Rgba([0, 0, 0, if x < 4 { 128 } else { 255 }])
+    }));
+    let map = StandardImagePreprocessor::default()
+        .width(8)
+        .alpha_relief(true)
+        .transform(&image);
+    let half = 1.0 - 128.0 / 255.0;
+    for (i, &l) in map.lightnesses().iter().enumerate() {
+        let expected = if i % 8 < 4 { half } else { 0.0 };
+        assert!((l - expected).abs() < 1e-6, "pixel {i} was {l}");
+    }
+
+    // The half transparent pixels stand about half as far above the white level
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map)
+        .unwrap();
+    let heights = common::surface_heights(&mesh, (8, 4), 1.0);
+    for (i, &z) in heights.iter().enumerate() {
+        let relief = z + 2.0;
+        let expected = if i % 8 < 4 { 2.0 - 2.0 * half } else { 2.0 };
+        assert!((relief - expected).abs() < 1e-5, "pixel {i} stood {relief}");
+    }
+}
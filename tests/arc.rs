@@ -0,0 +1,72 @@
+//! Tests that cylinders can be cut down to a partial arc and stay closed.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, LightMap, LithophaneGenerator, TriangleOrder,
+};
+use lith::geo::CheckStatus;
+
+fn map() -> LightMap {
+    LightMap::from_fn(12, 6, |x, y| ((x + 2 * y) % 5) as f32 / 5.0)
+}
+
+fn arc(order: TriangleOrder) -> CylinderMeshGenerator {
+    CylinderMeshGenerator::default()
+        .radius(20.0)
+        .height(10.0)
+        .scaling(2.0)
+        .order(order)
+        .arc(30.0, 150.0)
+}
+
+#[test]
+fn arc_is_closed_and_outward() {
+    for order in [
+        TriangleOrder::RowMajor,
+        TriangleOrder::Grouped,
+        TriangleOrder::Morton,
+    ] {
+        let estimate = arc(order).estimated_triangles((12, 6));
+        let mesh = arc(order).generate(map()).unwrap();
+        assert_eq!(mesh.to_indexed().1.len(), estimate);
+        let watertight = mesh.printability(0.0).check("Watertight").unwrap().status;
+        assert_eq!(watertight, CheckStatus::Pass);
+        assert!(mesh.volume() > 0.0);
+    }
+}
+
+#[test]
+fn arc_spans_the_requested_angles() {
+    let obj = arc(TriangleOrder::RowMajor)
+        .generate(map())
+        .unwrap()
+        .as_obj();
+    let angles: Vec<f32> = obj
+        .lines()
+        .filter_map(|l| l.strip_prefix("v "))
+        .map(|v| {
+            let v: Vec<f32> = v.split(' ').map(|c| c.parse().unwrap()).collect();
+            v[1].atan2(v[0]).to_degrees()
+        })
+        .collect();
+    let min = angles.iter().copied().fold(f32::MAX, f32::min);
+    let max = angles.iter().copied().fold(f32::MIN, f32::max);
+    assert!((min - 30.0).abs() < 1e-3, "arc starts at {min}");
+    assert!((max - 150.0).abs() < 1e-3, "arc ends at {max}");
+}
+
+#[test]
+fn full_turn_matches_the_default() {
+    let generate = |generator: CylinderMeshGenerator| {
+        generator
+            .radius(20.0)
+            .height(10.0)
+            .scaling(2.0)
+            .generate(map())
+            .unwrap()
+            .as_stl_bytes()
+    };
+    assert_eq!(
+        generate(CylinderMeshGenerator::default().arc(0.0, 360.0)),
+        generate(CylinderMeshGenerator::default())
+    );
+}
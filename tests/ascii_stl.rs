@@ -0,0 +1,88 @@
+//! Tests that ASCII STL output round-trips to the same triangles as the binary writer.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// A facet's normal followed by its vertices
+type Facet = [[f32; 3]; 4];
+
+/// Parse each facet in an ASCII STL, checking the structure as it goes
+fn parse_ascii_stl(stl: &str) -> (String, Vec<Facet>) {
+    let mut lines = stl.lines().map(str::trim);
+    let name = lines
+        .next()
+        .and_then(|l| l.strip_prefix("solid "))
+        .expect("missing solid header")
+        .to_string();
+    let mut facets = vec![];
+    loop {
+        let line = lines.next().expect("missing endsolid");
+        if line == format!("endsolid {name}") {
+            break;
+        }
+        let mut facet = [[0.0; 3]; 4];
+        facet[0] = parse_coords(line, "facet normal ");
+        assert_eq!(lines.next(), Some("outer loop"));
+        for vertex in &mut facet[1..] {
+            *vertex = parse_coords(lines.next().expect("missing vertex"), "vertex ");
+        }
+        assert_eq!(lines.next(), Some("endloop"));
+        assert_eq!(lines.next(), Some("endfacet"));
+        facets.push(facet);
+    }
+    assert_eq!(lines.next(), None);
+    (name, facets)
+}
+
+/// Parse the three numbers after `prefix` on a line
+fn parse_coords(line: &str, prefix: &str) -> [f32; 3] {
+    let coords: Vec<f32> = line
+        .strip_prefix(prefix)
+        .unwrap_or_else(|| panic!("expected {prefix:?}, found {line:?}"))
+        .split_whitespace()
+        .map(|c| c.parse().unwrap())
+        .collect();
+    coords.try_into().unwrap()
+}
+
+#[test]
+fn ascii_stl_round_trips() {
+    let map = LightMap::from_fn(12, 9, |x, y| ((x * 5 + y * 3) % 8) as f32 / 7.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map)
+        .unwrap();
+
+    let (name, facets) = parse_ascii_stl(&mesh.as_ascii_stl_string("test part"));
+    assert_eq!(name, "test part");
+
+    let binary = mesh.as_stl_bytes();
+    let count = u32::from_le_bytes(binary[80..84].try_into().unwrap()) as usize;
+    assert_eq!(facets.len(), count);
+    for (facet, triangle) in facets.iter().zip(binary[84..].chunks_exact(50)) {
+        for (i, vertex) in facet[1..].iter().enumerate() {
+            for (c, &coord) in vertex.iter().enumerate() {
+                let start = 12 + i * 12 + c * 4;
+                let expected = f32::from_le_bytes(triangle[start..start + 4].try_into().unwrap());
+                assert!((coord - expected).abs() <= expected.abs() * 1e-6);
+            }
+        }
+    }
+}
+
+#[test]
+fn ascii_stl_normals_are_unit_length() {
+    let map = LightMap::from_fn(6, 5, |x, y| ((x + y) % 3) as f32 / 4.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .pixel_size(3.0)
+        .generate(map)
+        .unwrap();
+
+    let stl = mesh.as_ascii_stl();
+    let (name, facets) = parse_ascii_stl(&stl);
+    assert_eq!(name, "lithophane");
+    for [n, ..] in facets {
+        let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+        assert!((length - 1.0).abs() < 1e-5, "normal has length {length}");
+    }
+}
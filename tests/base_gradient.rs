@@ -0,0 +1,48 @@
+//! Tests that the base gradient thickens the base towards the light.
+
+mod common;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+const SIZE: usize = 9;
+
+fn base(generator: FlatMeshGenerator) -> Vec<f32> {
+    let map = LightMap::from_fn(SIZE, SIZE, |_, _| 0.5);
+    let mesh = generator.scaling(2.0).base(1.0).generate(map).unwrap();
+    common::base_heights(&mesh, (SIZE, SIZE), 1.0)
+}
+
+#[test]
+fn base_is_lowest_under_the_light_and_rises_with_distance() {
+    let strength = 1.5;
+    let graded = base(FlatMeshGenerator::default().base_gradient((0.5, 0.5), strength));
+
+    // Directly over the light the base is the full strength thicker, and at the corners it
+    // is back to its normal thickness
+    let at = |x: usize, y: usize| graded[y * SIZE + x];
+    assert!((at(4, 4) - (-3.0 - strength)).abs() < 1e-5);
+    for (x, y) in [(0, 0), (8, 0), (0, 8), (8, 8)] {
+        assert!((at(x, y) + 3.0).abs() < 1e-5, "corner was {}", at(x, y));
+    }
+
+    // In between, the base rises steadily with the distance from the light
+    let distance = |i: usize| {
+        let (x, y) = ((i % SIZE) as f32 - 4.0, (i / SIZE) as f32 - 4.0);
+        x.hypot(y)
+    };
+    for i in 0..graded.len() {
+        for j in 0..graded.len() {
+            if distance(i) < distance(j) - 1e-4 {
+                assert!(graded[i] < graded[j], "{i} and {j} are out of order");
+            }
+        }
+    }
+}
+
+#[test]
+fn off_center_light_thickens_its_own_corner() {
+    let graded = base(FlatMeshGenerator::default().base_gradient((0.0, 0.0), 1.0));
+    assert!((graded[0] + 4.0).abs() < 1e-5);
+    assert!((graded[SIZE * SIZE - 1] + 3.0).abs() < 1e-5);
+    assert!(graded[SIZE - 1] > graded[0] && graded[SIZE - 1] < graded[SIZE * SIZE - 1]);
+}
@@ -0,0 +1,50 @@
+//! Tests that beveled steps replace hard transitions in the relief with an intermediate vertex.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Get the z coordinate of every vertex in a binary STL
+fn vertex_heights(stl: &[u8]) -> Vec<f32> {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                let z = 12 + i * 12 + 8;
+                f32::from_le_bytes(t[z..z + 4].try_into().unwrap())
+            })
+        })
+        .collect()
+}
+
+/// A 2x2 light map with a black sample in one corner, so the single cell has a full-depth jump
+fn step() -> LightMap {
+    LightMap::from_fn(2, 2, |x, y| if x == 0 && y == 0 { 0.0 } else { 1.0 })
+}
+
+#[test]
+fn large_jump_gets_a_bevel_vertex() {
+    let stl = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .bevel_steps(1.0)
+        .generate(step())
+        .unwrap()
+        .as_stl_bytes();
+    let heights = vertex_heights(&stl);
+    // One corner is at 0 and the rest at -2, so the bevel vertex sits at their average
+    assert!(heights.iter().any(|z| (z + 1.5).abs() < 1e-6));
+}
+
+#[test]
+fn small_jump_is_left_alone() {
+    let plain = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(step())
+        .unwrap()
+        .as_stl_bytes();
+    let beveled = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .bevel_steps(3.0)
+        .generate(step())
+        .unwrap()
+        .as_stl_bytes();
+    assert_eq!(plain, beveled);
+}
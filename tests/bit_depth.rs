@@ -0,0 +1,64 @@
+//! Tests that 16-bit images keep their extra precision in the light map.
+
+use std::collections::HashSet;
+
+use image::{DynamicImage, ImageBuffer, Luma};
+use lith::gen::{
+    standard_image::StandardImagePreprocessor, tiled_image::TiledImagePreprocessor,
+    ImagePreprocessor, LightMap,
+};
+
+/// A smooth 16-bit gradient over a narrow band of grays, where 8 bits leave few steps
+fn gradient16() -> DynamicImage {
+    DynamicImage::ImageLuma16(ImageBuffer::from_fn(256, 2, |x, _| {
+        Luma([20000 + x as u16 * 40])
+    }))
+}
+
+fn distinct(map: &LightMap) -> usize {
+    let bits: HashSet<u32> = map.lightnesses().iter().map(|l| l.to_bits()).collect();
+    bits.len()
+}
+
+#[test]
+fn sixteen_bit_gradient_has_less_banding() {
+    let image = gradient16();
+    let eight = DynamicImage::ImageLuma8(image.to_luma8());
+    let preprocessor = || StandardImagePreprocessor::default().width(256);
+
+    let deep = preprocessor().transform(&image);
+    let shallow = preprocessor().transform(&eight);
+    assert_eq!(deep.dims(), shallow.dims());
+    // 256 columns spanning about 40 8-bit levels
+    assert!(distinct(&shallow) <= 41);
+    assert_eq!(distinct(&deep), 256);
+
+    // Both paths agree on the overall tone
+    for (a, b) in deep.lightnesses().iter().zip(shallow.lightnesses()) {
+        assert!((a - b).abs() < 0.01);
+    }
+}
+
+#[test]
+fn tiled_preprocessor_keeps_sixteen_bits() {
+    let image = gradient16();
+    let standard = StandardImagePreprocessor::default()
+        .width(256)
+        .transform(&image);
+    let decoded = TiledImagePreprocessor::default()
+        .width(256)
+        .transform(&image);
+    assert_eq!(distinct(&decoded), 256);
+    for (a, b) in decoded.lightnesses().iter().zip(standard.lightnesses()) {
+        assert!((a - b).abs() < 1e-5);
+    }
+
+    // Streaming the PNG straight from disk keeps the 16-bit samples too
+    let path = std::env::temp_dir().join(format!("lith-{}-gradient16.png", std::process::id()));
+    image.save(&path).unwrap();
+    let streamed = TiledImagePreprocessor::default()
+        .width(256)
+        .transform_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(streamed.unwrap().lightnesses(), decoded.lightnesses());
+}
@@ -0,0 +1,115 @@
+//! Tests that the blur preprocessor smooths the light map.
+
+use image::{DynamicImage, GrayImage, RgbaImage};
+use lith::gen::{
+    blur_image::BlurImagePreprocessor, standard_image::StandardImagePreprocessor, ImagePreprocessor,
+};
+
+/// A noisy checkerboard of dark and light pixels
+fn noise() -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(12, 8, |x, y| {
+        image::Luma([if (x + y) % 2 == 0 { 40 } else { 200 }])
+    }))
+}
+
+fn spread(lightnesses: &[f32]) -> f32 {
+    let min = lightnesses.iter().copied().fold(f32::MAX, f32::min);
+    let max = lightnesses.iter().copied().fold(f32::MIN, f32::max);
+    max - min
+}
+
+#[test]
+fn zero_sigma_is_a_passthrough() {
+    let standard = StandardImagePreprocessor::default()
+        .width(12)
+        .transform(&noise());
+    let blurred = BlurImagePreprocessor::default()
+        .width(12)
+        .sigma(0.0)
+        .transform(&noise());
+    assert_eq!(blurred.dims(), standard.dims());
+    assert_eq!(blurred.lightnesses(), standard.lightnesses());
+}
+
+#[test]
+fn blur_flattens_noise() {
+    let standard = StandardImagePreprocessor::default()
+        .width(12)
+        .transform(&noise());
+    let blurred = BlurImagePreprocessor::default()
+        .width(12)
+        .sigma(1.5)
+        .transform(&noise());
+    assert_eq!(blurred.dims(), standard.dims());
+    assert!(spread(blurred.lightnesses()) < spread(standard.lightnesses()) / 2.0);
+}
+
+#[test]
+fn blur_spreads_a_bright_pixel() {
+    let dot = DynamicImage::ImageLuma8(GrayImage::from_fn(9, 9, |x, y| {
+        image::Luma([if (x, y) == (4, 4) { 255 } else { 0 }])
+    }));
+    let sharp = StandardImagePreprocessor::default()
+        .width(9)
+        .transform(&dot);
+    let blurred = BlurImagePreprocessor::default()
+        .width(9)
+        .radius(3.0)
+        .transform(&dot);
+
+    let at = |l: &[f32], x: usize, y: usize| l[y * 9 + x];
+    let (sharp, blurred) = (sharp.lightnesses(), blurred.lightnesses());
+    for (x, y) in [(3, 4), (5, 4), (4, 3), (4, 5), (3, 3)] {
+        assert!(
+            at(blurred, x, y) > at(sharp, x, y),
+            "({x}, {y}) didn't get lighter"
+        );
+    }
+    assert!(at(blurred, 4, 4) < at(sharp, 4, 4));
+}
+
+#[test]
+fn radius_is_two_standard_deviations() {
+    let by_radius = BlurImagePreprocessor::default()
+        .width(12)
+        .radius(3.0)
+        .transform(&noise());
+    let by_sigma = BlurImagePreprocessor::default()
+        .width(12)
+        .sigma(1.5)
+        .transform(&noise());
+    assert_eq!(by_radius.lightnesses(), by_sigma.lightnesses());
+}
+
+#[test]
+fn conversion_settings_match_the_standard_preprocessor() {
+    // Colored, half transparent pixels so every setting has something to change
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(12, 8, |x, y| {
+        image::Rgba([
+            x as u8 * 20,
+            y as u8 * 30,
+            90,
+            if x < 6 { 128 } else { 255 },
+        ])
+    }));
+    let standard = StandardImagePreprocessor::default()
+        .width(12)
+        .linear_gamma(1.8)
+        .perceptual(false)
+        .alpha_relief(true)
+        .transform(&image);
+    let blurred = BlurImagePreprocessor::default()
+        .width(12)
+        .linear_gamma(1.8)
+        .perceptual(false)
+        .alpha_relief(true)
+        .transform(&image);
+    assert_eq!(blurred.lightnesses(), standard.lightnesses());
+    assert_ne!(
+        blurred.lightnesses(),
+        BlurImagePreprocessor::default()
+            .width(12)
+            .transform(&image)
+            .lightnesses()
+    );
+}
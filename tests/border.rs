@@ -0,0 +1,68 @@
+//! Tests the raised border frame and hanging hole of flat lithophanes.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::{CheckStatus, Mesh};
+
+fn map() -> LightMap {
+    LightMap::from_fn(16, 10, |x, y| ((x * 3 + y) % 7) as f32 / 8.0)
+}
+
+fn framed() -> FlatMeshGenerator {
+    FlatMeshGenerator::default()
+        .scaling(2.0)
+        .pixel_size(0.5)
+        .border(6, 0.5)
+}
+
+fn vertices(mesh: &Mesh) -> Vec<[f32; 3]> {
+    mesh.as_obj()
+        .lines()
+        .filter_map(|l| l.strip_prefix("v "))
+        .map(|v| {
+            let v: Vec<f32> = v.split(' ').map(|c| c.parse().unwrap()).collect();
+            [v[0], v[1], v[2]]
+        })
+        .collect()
+}
+
+fn is_watertight(mesh: &Mesh) -> bool {
+    mesh.printability(0.0).check("Watertight").unwrap().status == CheckStatus::Pass
+}
+
+#[test]
+fn border_frames_the_relief() {
+    let estimate = framed().estimated_triangles((16, 10));
+    let mesh = framed().generate(map()).unwrap();
+    assert_eq!(mesh.to_indexed().1.len(), estimate);
+    assert!(is_watertight(&mesh));
+
+    // 16 + 2 * 6 samples 0.5 mm apart, standing 0.5 above the relief
+    let size = mesh.dimensions();
+    assert_eq!((size.x, size.y, size.z), (13.5, 10.5, 2.5));
+
+    // Every top vertex in the border band is at the frame height
+    let in_band = |v: &[f32; 3]| v[0] < 2.75 || v[0] > 10.75 || v[1] < 2.75 || v[1] > 7.75;
+    let band: Vec<_> = vertices(&mesh)
+        .into_iter()
+        .filter(|v| in_band(v) && v[2] > -2.0)
+        .collect();
+    assert!(!band.is_empty());
+    assert!(band.iter().all(|v| v[2] == 0.5));
+}
+
+#[test]
+fn hang_hole_goes_through_the_frame() {
+    let plain = framed().generate(map()).unwrap();
+    let estimate = framed().hang_hole(1.0, 1.5).estimated_triangles((16, 10));
+    let mesh = framed().hang_hole(1.0, 1.5).generate(map()).unwrap();
+    assert!(is_watertight(&mesh));
+    // The hole removes a 5 x 4 block of cells, walled on its 18 sides
+    assert_eq!(mesh.to_indexed().1.len(), estimate - 20 * 4 + 18 * 2);
+    assert!(mesh.volume() < plain.volume());
+
+    // Nothing is left inside the hole, centered at the top of the frame
+    for v in vertices(&mesh) {
+        let distance = (v[0] - 6.75).hypot(v[1] - 1.5);
+        assert!(distance > 1.0, "vertex {v:?} is inside the hole");
+    }
+}
@@ -0,0 +1,92 @@
+//! Tests for finding and recentering on the center of mass of a mesh.
+
+use lith::geo::{Mesh, Vec3};
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+/// Get the 12 outward facing triangles of the box from `lo` to `hi`
+fn cuboid(lo: [f32; 3], hi: [f32; 3]) -> Vec<[Vec3; 3]> {
+    let corner = |i: usize| {
+        vec3(
+            if i & 1 == 0 { lo[0] } else { hi[0] },
+            if i & 2 == 0 { lo[1] } else { hi[1] },
+            if i & 4 == 0 { lo[2] } else { hi[2] },
+        )
+    };
+    // Each face as four corners wound counter-clockwise seen from outside
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    faces
+        .iter()
+        .flat_map(|f| {
+            [
+                [corner(f[0]), corner(f[1]), corner(f[2])],
+                [corner(f[0]), corner(f[2]), corner(f[3])],
+            ]
+        })
+        .collect()
+}
+
+fn close(a: &Vec3, b: &Vec3) -> bool {
+    (a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4 && (a.z - b.z).abs() < 1e-4
+}
+
+/// Two separate boxes of volume 2 and 3, away from the origin
+fn asymmetric() -> Mesh {
+    let mut triangles = cuboid([10.0, -4.0, 2.0], [12.0, -3.0, 3.0]);
+    triangles.extend(cuboid([14.0, -4.0, 2.0], [15.0, -3.0, 5.0]));
+    Mesh::try_from_triangles(triangles).unwrap()
+}
+
+#[test]
+fn center_of_mass_matches_the_analytic_value() {
+    let mesh = asymmetric();
+    assert!(mesh.is_watertight());
+    assert!((mesh.volume() - 5.0).abs() < 1e-4);
+    // (2 * (11, -3.5, 2.5) + 3 * (14.5, -3.5, 3.5)) / 5
+    let com = mesh.center_of_mass();
+    assert!(close(&com, &vec3(13.1, -3.5, 3.1)), "{com:?}");
+    // Weighted by volume, it isn't the center of the bounding box
+    let (min, max) = mesh.bounds();
+    assert!((com.x - (min.x + max.x) / 2.0).abs() > 0.5);
+}
+
+#[test]
+fn recentering_moves_the_center_of_mass_to_the_origin() {
+    let mut mesh = asymmetric();
+    let (min, _) = mesh.bounds();
+    mesh.recenter_on_com();
+    assert!(close(&mesh.center_of_mass(), &vec3(0.0, 0.0, 0.0)));
+    // Only moved, by exactly the old center of mass
+    assert!((mesh.volume() - 5.0).abs() < 1e-4);
+    let (moved, _) = mesh.bounds();
+    assert!(close(&moved, &vec3(min.x - 13.1, min.y + 3.5, min.z - 3.1)));
+}
+
+#[test]
+fn mesh_without_volume_gives_the_origin() {
+    // Both sides of one square, which enclose nothing
+    let (a, b, c, d) = (
+        vec3(1.0, 1.0, 1.0),
+        vec3(2.0, 1.0, 1.0),
+        vec3(2.0, 2.0, 1.0),
+        vec3(1.0, 2.0, 1.0),
+    );
+    let sheet = Mesh::try_from_triangles(vec![
+        [a.clone(), b.clone(), c.clone()],
+        [a.clone(), c.clone(), d.clone()],
+        [a.clone(), c.clone(), b],
+        [a, d, c],
+    ])
+    .unwrap();
+    assert_eq!(sheet.volume(), 0.0);
+    assert!(close(&sheet.center_of_mass(), &vec3(0.0, 0.0, 0.0)));
+}
@@ -0,0 +1,39 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use lith::geo::Mesh;
+
+/// Get the z of the relief surface over each pixel of a flat lithophane of `dims` pixels
+/// `spacing` apart, row by row. The surface is the highest vertex at each grid position.
+pub fn surface_heights(mesh: &Mesh, dims: (usize, usize), spacing: f32) -> Vec<f32> {
+    grid_heights(mesh, dims, spacing, f32::MIN, f32::max)
+}
+
+/// Get the z of the base under each pixel of a flat lithophane, row by row. The base is the
+/// lowest vertex at each grid position.
+pub fn base_heights(mesh: &Mesh, dims: (usize, usize), spacing: f32) -> Vec<f32> {
+    grid_heights(mesh, dims, spacing, f32::MAX, f32::min)
+}
+
+/// Fold the z of every vertex at each grid position into `init` with `pick`
+fn grid_heights(
+    mesh: &Mesh,
+    dims: (usize, usize),
+    spacing: f32,
+    init: f32,
+    pick: fn(f32, f32) -> f32,
+) -> Vec<f32> {
+    let (width, height) = dims;
+    let mut heights = vec![init; width * height];
+    let grid = |c: f32, max: usize| {
+        let i = (c / spacing).round();
+        ((c / spacing - i).abs() < 1e-4 && i >= 0.0 && (i as usize) < max).then_some(i as usize)
+    };
+    for v in mesh.to_indexed().0 {
+        if let (Some(x), Some(y)) = (grid(v.x, width), grid(v.y, height)) {
+            let h = &mut heights[y * width + x];
+            *h = pick(*h, v.z);
+        }
+    }
+    heights
+}
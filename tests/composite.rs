@@ -0,0 +1,99 @@
+//! Tests for compositing an overlay onto a light map.
+
+use lith::gen::{BlendMode, GenError, LightMap};
+
+/// A 4x3 base of 0.6 with a 2x2 overlay of 0.3 placed at (1, 1), which overhangs nothing
+fn composite(mode: BlendMode) -> LightMap {
+    let mut base = LightMap::from_fn(4, 3, |_, _| 0.6);
+    let overlay = LightMap::from_fn(2, 2, |_, _| 0.3);
+    base.composite(&overlay, (1, 1), mode).unwrap();
+    base
+}
+
+/// Check the overlap region holds `expected` and everything else is untouched
+fn check(mode: BlendMode, expected: f32) {
+    let map = composite(mode);
+    for y in 0..3 {
+        for x in 0..4 {
+            let l = map.lightnesses()[y * 4 + x];
+            let inside = (1..3).contains(&x) && (1..3).contains(&y);
+            let want = if inside { expected } else { 0.6 };
+            assert!((l - want).abs() < 1e-6, "{mode:?} at ({x}, {y}): {l}");
+        }
+    }
+}
+
+#[test]
+fn add_sums_and_clamps() {
+    check(BlendMode::Add, 0.9);
+    let mut map = LightMap::from_fn(1, 1, |_, _| 0.8);
+    map.composite(&LightMap::from_fn(1, 1, |_, _| 0.5), (0, 0), BlendMode::Add)
+        .unwrap();
+    assert_eq!(map.lightnesses(), [1.0]);
+}
+
+#[test]
+fn subtract_differences_and_clamps() {
+    check(BlendMode::Subtract, 0.3);
+    let mut map = LightMap::from_fn(1, 1, |_, _| 0.2);
+    map.composite(
+        &LightMap::from_fn(1, 1, |_, _| 0.5),
+        (0, 0),
+        BlendMode::Subtract,
+    )
+    .unwrap();
+    assert_eq!(map.lightnesses(), [0.0]);
+}
+
+#[test]
+fn replace_uses_the_overlay() {
+    check(BlendMode::Replace, 0.3);
+}
+
+#[test]
+fn max_keeps_the_lighter() {
+    check(BlendMode::Max, 0.6);
+}
+
+#[test]
+fn min_keeps_the_darker() {
+    check(BlendMode::Min, 0.3);
+}
+
+#[test]
+fn overlay_past_the_edge_is_cropped() {
+    let mut base = LightMap::from_fn(3, 3, |_, _| 0.5);
+    base.composite(
+        &LightMap::from_fn(4, 4, |_, _| 0.0),
+        (2, 2),
+        BlendMode::Replace,
+    )
+    .unwrap();
+    let changed: Vec<usize> = (0..9).filter(|&i| base.lightnesses()[i] == 0.0).collect();
+    assert_eq!(changed, [8]);
+}
+
+#[test]
+fn mismatched_maps_are_an_error() {
+    // Too few lightnesses for their dimensions, which would index past the end
+    let short = LightMap::new(vec![0.5; 5], (3, 2));
+    let mut base = LightMap::from_fn(4, 3, |_, _| 0.6);
+    assert_eq!(
+        base.composite(&short, (0, 0), BlendMode::Replace),
+        Err(GenError::DimsMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+    assert!(base.lightnesses().iter().all(|&l| l == 0.6));
+
+    let mut short = LightMap::new(vec![0.5; 5], (3, 2));
+    let overlay = LightMap::from_fn(1, 1, |_, _| 0.0);
+    assert_eq!(
+        short.composite(&overlay, (0, 0), BlendMode::Replace),
+        Err(GenError::DimsMismatch {
+            expected: 6,
+            found: 5
+        })
+    );
+}
@@ -0,0 +1,45 @@
+//! Tests that contour relief turns a gradient into the expected number of terraces.
+
+use std::collections::BTreeSet;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator, ReliefProfile};
+
+fn gradient() -> LightMap {
+    LightMap::from_fn(41, 3, |x, _| x as f32 / 40.0)
+}
+
+/// Count the distinct z values of the vertices in a binary STL
+fn z_levels(stl: &[u8]) -> usize {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                let z = 12 + i * 12 + 8;
+                (f32::from_le_bytes(t[z..z + 4].try_into().unwrap()) * 1e4).round() as i64
+            })
+        })
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+#[test]
+fn gradient_has_one_level_per_band() {
+    for (interval, levels) in [(0.25, 5), (0.3, 4), (0.5, 3)] {
+        let mut map = gradient();
+        map.apply_profile(ReliefProfile::Contour(interval));
+        let bands = map.histogram(1000).iter().filter(|&&n| n > 0).count();
+        assert_eq!(bands, levels, "interval {interval}");
+    }
+}
+
+#[test]
+fn terraces_are_flat() {
+    let stl = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .contour(0.3)
+        .generate(gradient())
+        .unwrap()
+        .as_stl_bytes();
+    // Terraces at 0, 0.3, 0.6 and 0.9, plus the flat back
+    assert_eq!(z_levels(&stl), 5);
+}
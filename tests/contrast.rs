@@ -0,0 +1,110 @@
+//! Tests the gamma and contrast adjustments of the contrast preprocessor.
+
+use image::{DynamicImage, RgbImage};
+use lith::gen::{
+    contrast_image::ContrastImagePreprocessor, standard_image::StandardImagePreprocessor,
+    ImagePreprocessor,
+};
+use lith::img::LuminanceWeights;
+
+/// A gray ramp from black to white, with a little color so every channel matters
+fn ramp() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(16, 4, |x, y| {
+        let v = (x * 17) as u8;
+        image::Rgb([v, v.saturating_add(y as u8 * 3), v])
+    }))
+}
+
+#[test]
+fn neutral_settings_change_nothing() {
+    let standard = StandardImagePreprocessor::default()
+        .width(16)
+        .transform(&ramp());
+    let contrast = ContrastImagePreprocessor::default()
+        .width(16)
+        .gamma(1.0)
+        .contrast(1.0)
+        .transform(&ramp());
+    assert_eq!(contrast.dims(), standard.dims());
+    assert_eq!(contrast.lightnesses(), standard.lightnesses());
+}
+
+#[test]
+fn adjusted_lightness_stays_in_range() {
+    let standard = StandardImagePreprocessor::default()
+        .width(16)
+        .transform(&ramp());
+    for (gamma, contrast) in [(0.5, 3.0), (2.2, 10.0), (1.0, -2.0)] {
+        let map = ContrastImagePreprocessor::default()
+            .width(16)
+            .gamma(gamma)
+            .contrast(contrast)
+            .transform(&ramp());
+        for (&l, &s) in map.lightnesses().iter().zip(standard.lightnesses()) {
+            assert!((0.0..=1.0).contains(&l), "lightness {l} out of range");
+            let expected = ((s - 0.5) * contrast + 0.5).clamp(0.0, 1.0).powf(gamma);
+            assert!((l - expected).abs() < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn invert_applies_before_the_adjustments() {
+    let inverted = StandardImagePreprocessor::default()
+        .width(16)
+        .invert(true)
+        .transform(&ramp());
+    let contrast = ContrastImagePreprocessor::default()
+        .width(16)
+        .invert(true)
+        .contrast(1.5)
+        .gamma(0.8)
+        .transform(&ramp());
+    for (&l, &c) in inverted.lightnesses().iter().zip(contrast.lightnesses()) {
+        let expected = ((l - 0.5) * 1.5 + 0.5).clamp(0.0, 1.0).powf(0.8);
+        assert!(
+            (c - expected).abs() < 1e-6,
+            "{l} became {c}, not {expected}"
+        );
+    }
+    // The dark end of the ramp is now the light one
+    assert!(contrast.lightnesses()[0] > contrast.lightnesses()[15]);
+}
+
+#[test]
+fn conversion_settings_are_forwarded() {
+    let image = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(16, 4, |x, y| {
+        image::Rgba([
+            x as u8 * 16,
+            200,
+            y as u8 * 60,
+            if x < 8 { 100 } else { 255 },
+        ])
+    }));
+    let standard = StandardImagePreprocessor::default()
+        .width(16)
+        .weights(LuminanceWeights::REC601)
+        .linear_gamma(1.5)
+        .perceptual(false)
+        .alpha_relief(true)
+        .alpha_threshold(0.2)
+        .transparent_lightness(0.3)
+        .transform(&image);
+    let contrast = ContrastImagePreprocessor::default()
+        .width(16)
+        .weights(LuminanceWeights::REC601)
+        .linear_gamma(1.5)
+        .perceptual(false)
+        .alpha_relief(true)
+        .alpha_threshold(0.2)
+        .transparent_lightness(0.3)
+        .transform(&image);
+    assert_eq!(contrast.lightnesses(), standard.lightnesses());
+    assert_ne!(
+        contrast.lightnesses(),
+        ContrastImagePreprocessor::default()
+            .width(16)
+            .transform(&image)
+            .lightnesses()
+    );
+}
@@ -0,0 +1,60 @@
+//! Tests that a threshold cutout keeps exactly the footprint of the dark shape.
+
+use std::collections::BTreeSet;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Whether the pixel at (x, y) is part of the dark L shape
+fn in_shape(x: usize, y: usize) -> bool {
+    let horizontal = (2..=8).contains(&x) && (2..=4).contains(&y);
+    let vertical = (2..=4).contains(&x) && (2..=8).contains(&y);
+    horizontal || vertical
+}
+
+#[test]
+fn cutout_back_covers_exactly_the_shape() {
+    let map = LightMap::from_fn(12, 11, |x, y| if in_shape(x, y) { 0.1 } else { 0.9 });
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .threshold_cutout(0.5)
+        .generate(map)
+        .unwrap();
+    assert!(mesh.is_watertight());
+
+    // Find the cells under each downward facing triangle of the back
+    let (vertices, triangles) = mesh.to_indexed();
+    let mut back = BTreeSet::new();
+    let mut area = 0.0;
+    for t in triangles {
+        let [a, b, c] = t.map(|i| &vertices[i]);
+        let facing = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x);
+        if facing < -1e-6 {
+            area -= facing / 2.0;
+            let cx = (a.x + b.x + c.x) / 3.0;
+            let cy = (a.y + b.y + c.y) / 3.0;
+            back.insert((cx.floor() as usize, cy.floor() as usize));
+        }
+    }
+
+    // A cell survives when all four of its corners are dark
+    let expected: BTreeSet<_> = (0..11)
+        .flat_map(|x| (0..10).map(move |y| (x, y)))
+        .filter(|&(x, y)| {
+            in_shape(x, y) && in_shape(x + 1, y) && in_shape(x, y + 1) && in_shape(x + 1, y + 1)
+        })
+        .collect();
+    assert_eq!(expected.len(), 20);
+    assert_eq!(back, expected);
+    assert!((area - 20.0).abs() < 1e-4, "the back covered {area}");
+}
+
+#[test]
+fn nothing_thick_enough_leaves_nothing() {
+    let map = LightMap::from_fn(6, 6, |_, _| 0.9);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .threshold_cutout(0.5)
+        .generate(map)
+        .unwrap();
+    assert!(mesh.to_indexed().1.is_empty());
+}
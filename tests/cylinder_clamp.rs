@@ -0,0 +1,43 @@
+//! Tests that cylinder relief deeper than the radius is clamped instead of turning the mesh
+//! inside out.
+
+use lith::gen::{cylinder_mesh::CylinderMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Get the smallest distance of any vertex in a binary STL from the z axis
+fn min_radius(stl: &[u8]) -> f32 {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                let coord = |c: usize| {
+                    let start = 12 + i * 12 + c * 4;
+                    f32::from_le_bytes(t[start..start + 4].try_into().unwrap())
+                };
+                coord(0).hypot(coord(1))
+            })
+        })
+        .fold(f32::MAX, f32::min)
+}
+
+#[test]
+fn oversized_scaling_is_clamped() {
+    let generator = CylinderMeshGenerator::default()
+        .scaling(50.0)
+        .radius(10.0)
+        .height(10.0);
+    let (inner, outer) = generator.relief_range();
+    assert!(inner > 0.0 && inner < outer, "range was {inner}..{outer}");
+
+    let map = LightMap::from_fn(24, 8, |x, _| (x % 4) as f32 / 3.0);
+    let stl = generator.generate(map).unwrap().as_stl_bytes();
+    assert!((min_radius(&stl) - inner).abs() < 1e-4);
+}
+
+#[test]
+fn reasonable_scaling_is_untouched() {
+    let generator = CylinderMeshGenerator::default()
+        .scaling(2.0)
+        .radius(10.0)
+        .height(10.0);
+    assert_eq!(generator.relief_range(), (8.0, 10.0));
+}
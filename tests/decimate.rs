@@ -0,0 +1,51 @@
+//! Tests for simplifying meshes with quadric error decimation.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::Mesh;
+
+fn relief() -> Mesh {
+    // A gentle dome, which leaves plenty of nearly flat areas to collapse
+    let map = LightMap::from_fn(24, 24, |x, y| {
+        let (dx, dy) = (x as f32 - 11.5, y as f32 - 11.5);
+        0.2 + 0.6 * (dx.hypot(dy) / 16.3)
+    });
+    FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base(1.0)
+        .generate(map)
+        .unwrap()
+}
+
+#[test]
+fn decimation_reaches_the_target_and_keeps_the_bounds() {
+    let mesh = relief();
+    let before = mesh.to_indexed().1.len();
+    let target = before / 3;
+
+    let simplified = mesh.decimate_to(target);
+    let after = simplified.to_indexed().1.len();
+    assert!(after <= target, "{after} triangles left of {before}");
+    assert!(after > 0);
+    assert!(simplified.is_watertight());
+
+    let ((min, max), (new_min, new_max)) = (mesh.bounds(), simplified.bounds());
+    for (a, b) in [
+        (min.x, new_min.x),
+        (min.y, new_min.y),
+        (min.z, new_min.z),
+        (max.x, new_max.x),
+        (max.y, new_max.y),
+        (max.z, new_max.z),
+    ] {
+        assert!((a - b).abs() < 0.05, "bound moved from {a} to {b}");
+    }
+}
+
+#[test]
+fn target_above_the_count_changes_nothing() {
+    let mesh = relief();
+    let count = mesh.to_indexed().1.len();
+    let same = mesh.decimate_to(count + 10);
+    assert_eq!(same.to_indexed().1.len(), count);
+    assert!((same.volume() - mesh.volume()).abs() < 1e-3);
+}
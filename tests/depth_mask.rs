@@ -0,0 +1,40 @@
+//! Tests that a depth mask scales the relief of each pixel by the mask value.
+
+mod common;
+
+use image::{GrayImage, Luma};
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+fn surface(mask: GrayImage) -> Vec<f32> {
+    let map = LightMap::from_fn(6, 3, |_, _| 0.6);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base(1.0)
+        .depth_mask(mask)
+        .generate(map)
+        .unwrap();
+    common::surface_heights(&mesh, (6, 3), 1.0)
+}
+
+#[test]
+fn displacement_is_proportional_to_the_mask() {
+    let mask = GrayImage::from_fn(6, 3, |x, _| Luma([x as u8 * 51]));
+    let heights = surface(mask);
+    // Unmasked, a lightness of 0.6 sinks the surface 1.2 below the top
+    for (i, &z) in heights.iter().enumerate() {
+        let weight = (i % 6) as f32 / 5.0;
+        assert!(
+            (z + 1.2 * weight).abs() < 1e-5,
+            "pixel {i} was at {z} for a weight of {weight}"
+        );
+    }
+    // Black keeps the surface at the top and white gives the full depth
+    assert_eq!(heights[0], 0.0);
+    assert!((heights[5] + 1.2).abs() < 1e-5);
+}
+
+#[test]
+fn mask_is_resized_to_the_light_map() {
+    let heights = surface(GrayImage::from_pixel(12, 6, Luma([153])));
+    assert!(heights.iter().all(|z| (z + 0.72).abs() < 1e-5));
+}
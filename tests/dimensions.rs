@@ -0,0 +1,27 @@
+//! Tests that the bounding box and dimensions match the size of the generated lithophane.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::Mesh;
+
+#[test]
+fn flat_mesh_dimensions_match_map() {
+    // 11 x 6 samples 2 mm apart make a 20 x 10 mm plate
+    let mesh = FlatMeshGenerator::default()
+        .scaling(3.0)
+        .pixel_size(2.0)
+        .generate(LightMap::from_fn(11, 6, |x, _| (x % 2) as f32))
+        .unwrap();
+
+    let (min, max) = mesh.bounds();
+    assert_eq!((min.x, min.y, min.z), (0.0, 0.0, -3.0));
+    assert_eq!((max.x, max.y, max.z), (20.0, 10.0, 0.0));
+    let size = mesh.dimensions();
+    assert_eq!((size.x, size.y, size.z), (20.0, 10.0, 3.0));
+}
+
+#[test]
+fn empty_mesh_has_zero_dimensions() {
+    let mesh = Mesh::new(vec![]);
+    let size = mesh.dimensions();
+    assert_eq!((size.x, size.y, size.z), (0.0, 0.0, 0.0));
+}
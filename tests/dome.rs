@@ -0,0 +1,45 @@
+//! Tests for the hemispherical dome generator.
+
+use lith::gen::{dome_mesh::DomeMeshGenerator, GenError, LightMap, LithophaneGenerator};
+use lith::geo::Vec3;
+
+fn map() -> LightMap {
+    LightMap::from_fn(16, 6, |x, y| ((x + 2 * y) % 5) as f32 / 5.0)
+}
+
+fn length(v: &Vec3) -> f32 {
+    (v.x * v.x + v.y * v.y + v.z * v.z).sqrt()
+}
+
+#[test]
+fn dome_is_closed_and_sized() {
+    let generator = DomeMeshGenerator::default()
+        .radius(30.0)
+        .scaling(3.0)
+        .opening(20.0);
+    let estimate = generator.estimated_triangles(map().dims());
+    let (inner, outer) = generator.relief_range();
+    assert_eq!((inner, outer), (27.0, 30.0));
+
+    let mesh = generator.generate(map()).unwrap();
+    assert!(mesh.is_watertight());
+    assert!(mesh.volume() > 0.0);
+
+    let (vertices, triangles) = mesh.to_indexed();
+    assert_eq!(triangles.len(), estimate);
+    for v in &vertices {
+        let r = length(v);
+        assert!(r > inner - 1e-3 && r < outer + 1e-3, "vertex at radius {r}");
+        assert!(v.z > -1e-4);
+    }
+    // The equator sits on the bed and the opening leaves the top clear
+    let (min, max) = mesh.bounds();
+    assert!(min.z.abs() < 1e-4);
+    assert!(max.z < outer * 20f32.to_radians().cos() + 1e-3);
+}
+
+#[test]
+fn dome_needs_a_radius() {
+    let result = DomeMeshGenerator::default().scaling(2.0).generate(map());
+    assert_eq!(result.err(), Some(GenError::EmptyDimensions("radius")));
+}
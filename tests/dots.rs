@@ -0,0 +1,41 @@
+//! Tests for the halftone dot generator.
+
+use lith::gen::{dot_mesh::DotMeshGenerator, LightMap, LithophaneGenerator};
+
+#[test]
+fn one_dot_per_sample_taller_when_darker() {
+    // Lightness rises along each row
+    let map = LightMap::from_fn(5, 3, |x, y| (x + y) as f32 / 8.0);
+    let mesh = DotMeshGenerator::default()
+        .scaling(2.0)
+        .base(1.0)
+        .generate(map)
+        .unwrap();
+    let vertices = mesh.to_indexed().0;
+
+    // Each dot is centered on its sample, with the middle of its bottom disc at z = 0
+    let centers = vertices
+        .iter()
+        .filter(|v| v.z == 0.0 && v.x.fract() == 0.0 && v.y.fract() == 0.0)
+        .count();
+    assert_eq!(centers, 15);
+
+    // The tip of each dot is the highest point over its center
+    let tip = |x: f32, y: f32| {
+        vertices
+            .iter()
+            .filter(|v| v.x == x && v.y == y)
+            .map(|v| v.z)
+            .fold(f32::MIN, f32::max)
+    };
+    for y in 0..3 {
+        for x in 0..5 {
+            let lightness = (x + y) as f32 / 8.0;
+            let expected = 1.0 + (1.0 - lightness) * 2.0;
+            assert!((tip(x as f32, y as f32) - expected).abs() < 1e-5);
+        }
+        for x in 1..5 {
+            assert!(tip(x as f32, y as f32) < tip(x as f32 - 1.0, y as f32));
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Tests for the draft angle on the brim walls of flat lithophanes.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+fn map() -> LightMap {
+    LightMap::from_fn(8, 6, |x, y| ((x + y) % 4) as f32 / 5.0)
+}
+
+#[test]
+fn draft_grows_the_footprint_by_the_depth() {
+    let depth = 3.0;
+    let generate = |degrees| {
+        FlatMeshGenerator::default()
+            .max_thickness(depth)
+            .draft_angle(degrees)
+            .generate(map())
+            .unwrap()
+    };
+    let (straight_min, straight_max) = generate(0.0).bounds();
+    let (min, max) = generate(10.0).bounds();
+
+    let grow = 10f32.to_radians().tan() * depth;
+    for (drafted, straight) in [
+        (min.x, straight_min.x - grow),
+        (min.y, straight_min.y - grow),
+        (max.x, straight_max.x + grow),
+        (max.y, straight_max.y + grow),
+    ] {
+        assert!((drafted - straight).abs() < 1e-4, "{drafted} vs {straight}");
+    }
+    // Only the base grows, so the depth is unchanged
+    assert_eq!((min.z, max.z), (straight_min.z, straight_max.z));
+}
+
+#[test]
+fn drafted_mesh_stays_closed() {
+    let mesh = FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .draft_angle(20.0)
+        .generate(map())
+        .unwrap();
+    assert!(mesh.is_watertight());
+}
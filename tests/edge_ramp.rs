@@ -0,0 +1,48 @@
+//! Tests that the edge ramp fades the relief down to the base at the edge of the image.
+
+use std::collections::HashMap;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Get the highest z of the vertices at each (x, y) position in a binary STL
+fn top_heights(stl: &[u8]) -> HashMap<(i64, i64), f32> {
+    let mut heights = HashMap::new();
+    for t in stl[84..].chunks_exact(50) {
+        for i in 0..3 {
+            let coord = |c: usize| {
+                let start = 12 + i * 12 + c * 4;
+                f32::from_le_bytes(t[start..start + 4].try_into().unwrap())
+            };
+            let key = (coord(0).round() as i64, coord(1).round() as i64);
+            let z = heights.entry(key).or_insert(f32::MIN);
+            *z = z.max(coord(2));
+        }
+    }
+    heights
+}
+
+#[test]
+fn edge_heights_ramp_to_base() {
+    let map = LightMap::from_fn(20, 20, |_, _| 0.0);
+    let stl = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .edge_ramp(4)
+        .generate(map)
+        .unwrap()
+        .as_stl_bytes();
+    let heights = top_heights(&stl);
+
+    // Full relief is 2 thick over a base at -2, so each step inwards adds a quarter of it
+    for (x, expected) in [
+        (0, -2.0),
+        (1, -1.5),
+        (2, -1.0),
+        (3, -0.5),
+        (4, 0.0),
+        (10, 0.0),
+    ] {
+        assert_eq!(heights[&(x, 10)], expected, "height at x = {x}");
+        assert_eq!(heights[&(19 - x, 10)], expected, "height at x = {}", 19 - x);
+        assert_eq!(heights[&(10, x)], expected, "height at y = {x}");
+    }
+}
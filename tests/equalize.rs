@@ -0,0 +1,141 @@
+//! Tests that histogram equalization stretches a narrow range of tones.
+
+use image::{DynamicImage, GrayImage};
+use lith::gen::{
+    equalize_image::EqualizeImagePreprocessor, standard_image::StandardImagePreprocessor,
+    ImagePreprocessor,
+};
+use lith::img::LuminanceWeights;
+
+/// A low-contrast gradient covering only a narrow band of grays
+fn dull_gradient() -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(32, 4, |x, _| {
+        image::Luma([100 + x as u8])
+    }))
+}
+
+fn range(lightnesses: &[f32]) -> (f32, f32) {
+    let min = lightnesses.iter().copied().fold(f32::MAX, f32::min);
+    let max = lightnesses.iter().copied().fold(f32::MIN, f32::max);
+    (min, max)
+}
+
+#[test]
+fn equalized_gradient_spans_the_full_range() {
+    let original = StandardImagePreprocessor::default()
+        .width(32)
+        .transform(&dull_gradient());
+    let equalized = EqualizeImagePreprocessor::default()
+        .width(32)
+        .transform(&dull_gradient());
+
+    let (min, max) = range(original.lightnesses());
+    assert!(max - min < 0.2);
+    let (min, max) = range(equalized.lightnesses());
+    assert_eq!((min, max), (0.0, 1.0));
+}
+
+#[test]
+fn strength_blends_with_the_original() {
+    let original = StandardImagePreprocessor::default()
+        .width(32)
+        .transform(&dull_gradient());
+    let off = EqualizeImagePreprocessor::default()
+        .width(32)
+        .strength(0.0)
+        .transform(&dull_gradient());
+    assert_eq!(off.lightnesses(), original.lightnesses());
+
+    let (min, max) = range(
+        EqualizeImagePreprocessor::default()
+            .width(32)
+            .strength(0.5)
+            .transform(&dull_gradient())
+            .lightnesses(),
+    );
+    let (original_min, original_max) = range(original.lightnesses());
+    assert!(max - min > original_max - original_min);
+    assert!(max - min < 1.0);
+}
+
+#[test]
+fn equalization_follows_the_light_map_histogram() {
+    // sRGB 0 and 1 are both in the first of 256 histogram bins, so they equalize alike
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(12, 2, |x, _| {
+        image::Luma([[0, 1, 90, 200][x as usize / 3]])
+    }));
+    let original = StandardImagePreprocessor::default().transform(&image);
+    let equalized = EqualizeImagePreprocessor::default().transform(&image);
+
+    let counts = original.histogram(256);
+    let bin = |l: f32| ((l * 256.0) as usize).min(255);
+    assert_eq!(
+        bin(original.lightnesses()[0]),
+        bin(original.lightnesses()[3])
+    );
+
+    // Each tone maps to the share of pixels in or below its bin, above the darkest bin
+    let first = counts.iter().find(|&&c| c > 0).copied().unwrap() as f32;
+    let total = original.lightnesses().len() as f32;
+    for (&l, &e) in original.lightnesses().iter().zip(equalized.lightnesses()) {
+        let below = counts[..=bin(l)].iter().sum::<u32>() as f32;
+        let expected = (below - first) / (total - first);
+        assert!(
+            (e - expected).abs() < 1e-6,
+            "{l} became {e}, not {expected}"
+        );
+    }
+    assert_eq!(equalized.lightnesses()[0], equalized.lightnesses()[3]);
+    assert_eq!(equalized.lightnesses()[0], 0.0);
+}
+
+#[test]
+fn invert_applies_before_the_remap() {
+    let equalized = EqualizeImagePreprocessor::default()
+        .width(32)
+        .transform(&dull_gradient());
+    let inverted = EqualizeImagePreprocessor::default()
+        .width(32)
+        .invert(true)
+        .transform(&dull_gradient());
+    // The inverted tones are spread over the full range too, with the dark end now light
+    assert_eq!(range(inverted.lightnesses()), (0.0, 1.0));
+    assert_eq!(inverted.lightnesses()[0], 1.0);
+    assert_eq!(equalized.lightnesses()[0], 0.0);
+    for (&e, &i) in equalized.lightnesses().iter().zip(inverted.lightnesses()) {
+        assert!(e <= 0.5 || i <= 0.5, "{e} and {i} are both light");
+    }
+}
+
+#[test]
+fn conversion_settings_are_forwarded() {
+    let image = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(32, 4, |x, y| {
+        image::Rgba([
+            x as u8 * 8,
+            200,
+            y as u8 * 60,
+            if x < 16 { 100 } else { 255 },
+        ])
+    }));
+    let standard = StandardImagePreprocessor::default()
+        .width(32)
+        .weights(LuminanceWeights::REC601)
+        .linear_gamma(1.5)
+        .perceptual(false)
+        .alpha_relief(true)
+        .alpha_threshold(0.2)
+        .transparent_lightness(0.3)
+        .transform(&image);
+    // With no strength the equalizer passes the converted lightness straight through
+    let equalized = EqualizeImagePreprocessor::default()
+        .width(32)
+        .strength(0.0)
+        .weights(LuminanceWeights::REC601)
+        .linear_gamma(1.5)
+        .perceptual(false)
+        .alpha_relief(true)
+        .alpha_threshold(0.2)
+        .transparent_lightness(0.3)
+        .transform(&image);
+    assert_eq!(equalized.lightnesses(), standard.lightnesses());
+}
@@ -0,0 +1,50 @@
+//! Tests that the estimated triangle counts match the generated meshes.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator,
+};
+
+const DIMS: (usize, usize) = (9, 6);
+
+fn map() -> LightMap {
+    LightMap::from_fn(DIMS.0, DIMS.1, |x, y| ((x * 3 + y) % 7) as f32 / 8.0)
+}
+
+fn assert_flat_estimate(generator: FlatMeshGenerator, name: &str) {
+    let estimate = generator.estimated_triangles(DIMS);
+    let actual = generator.generate(map()).unwrap().to_indexed().1.len();
+    assert_eq!(estimate, actual, "{name}");
+}
+
+fn assert_cylinder_estimate(generator: CylinderMeshGenerator, name: &str) {
+    let estimate = generator.estimated_triangles(DIMS);
+    let actual = generator.generate(map()).unwrap().to_indexed().1.len();
+    assert_eq!(estimate, actual, "{name}");
+}
+
+#[test]
+fn flat_estimate_matches_the_mesh() {
+    let flat = || FlatMeshGenerator::default().scaling(2.0).base(0.5);
+    assert_flat_estimate(flat(), "plain");
+    assert_flat_estimate(flat().border(2, 1.0), "border");
+    assert_flat_estimate(flat().subdivide_smooth(1), "subdivided");
+    assert_flat_estimate(flat().mirrored_back(true), "mirrored back");
+    assert_flat_estimate(flat().base_gradient((0.5, 0.5), 1.0), "base gradient");
+    // A cutout low enough to keep every cell
+    assert_flat_estimate(flat().threshold_cutout(-1.0), "cutout");
+}
+
+#[test]
+fn cylinder_estimate_matches_the_mesh() {
+    let cylinder = || {
+        CylinderMeshGenerator::default()
+            .scaling(2.0)
+            .radius(20.0)
+            .height(10.0)
+    };
+    assert_cylinder_estimate(cylinder(), "plain");
+    assert_cylinder_estimate(cylinder().tiles(3), "tiled");
+    assert_cylinder_estimate(cylinder().arc(0.0, 120.0), "arc");
+    assert_cylinder_estimate(cylinder().keep_aspect(true), "keep aspect");
+}
@@ -0,0 +1,32 @@
+//! Tests for flattening a lightness range into a flat-topped relief.
+
+mod common;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+#[test]
+fn flattened_range_is_one_height() {
+    // Lightness rises from 0.0 to 1.0 across the columns, with a little noise down the rows
+    let mut map = LightMap::from_fn(11, 4, |x, y| x as f32 / 10.0 + y as f32 * 0.01);
+    map.flatten_range(0.3, 0.65, 0.5);
+    let mesh = FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .generate(map)
+        .unwrap();
+    let heights = common::surface_heights(&mesh, (11, 4), 1.0);
+
+    let flattened: Vec<f32> = heights
+        .chunks_exact(11)
+        .flat_map(|row| row[3..=6].to_vec())
+        .collect();
+    assert!(
+        flattened.iter().all(|&h| h == flattened[0]),
+        "{flattened:?}"
+    );
+    // The top is at the level's depth, and the pixels outside the range keep their own heights
+    assert!((flattened[0] - -1.0).abs() < 1e-6);
+    for row in heights.chunks_exact(11) {
+        assert!(row[2] > flattened[0] && row[7] < flattened[0]);
+        assert!(row[..3].windows(2).all(|w| w[0] > w[1]));
+    }
+}
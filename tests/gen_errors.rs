@@ -0,0 +1,62 @@
+//! Tests that generators report bad input as errors instead of panicking.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, dot_mesh::DotMeshGenerator, flat_mesh::FlatMeshGenerator,
+    GenError, LightMap, LithophaneGenerator,
+};
+
+fn empty() -> LightMap {
+    LightMap::new(vec![], (0, 4))
+}
+
+#[test]
+fn empty_light_map_is_an_error() {
+    assert_eq!(
+        FlatMeshGenerator::default().generate(empty()).err(),
+        Some(GenError::EmptyLightMap)
+    );
+    assert_eq!(
+        CylinderMeshGenerator::default().generate(empty()).err(),
+        Some(GenError::EmptyLightMap)
+    );
+    assert_eq!(
+        DotMeshGenerator::default().generate(empty()).err(),
+        Some(GenError::EmptyLightMap)
+    );
+}
+
+#[test]
+fn zero_sizes_are_errors() {
+    let map = || LightMap::from_fn(4, 4, |_, _| 0.5);
+    let err = CylinderMeshGenerator::default()
+        .radius(0.0)
+        .generate(map())
+        .err()
+        .unwrap();
+    assert_eq!(err, GenError::EmptyDimensions("radius"));
+    assert_eq!(err.to_string(), "the radius must be positive");
+
+    let err = FlatMeshGenerator::default()
+        .pixel_size(0.0)
+        .generate(map())
+        .err()
+        .unwrap();
+    assert_eq!(err, GenError::EmptyDimensions("pixel size"));
+}
+
+#[test]
+fn mismatched_dims_are_errors() {
+    let map = LightMap::new(vec![0.0; 5], (3, 2));
+    let err = FlatMeshGenerator::default().generate(map).err().unwrap();
+    assert_eq!(
+        err,
+        GenError::DimsMismatch {
+            expected: 6,
+            found: 5
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "the light map should have 6 lightnesses but has 5"
+    );
+}
@@ -0,0 +1,61 @@
+//! Golden-file tests for the STL output.
+//!
+//! Each test generates a lithophane from `tests/fixtures/gradient.png` and compares the full
+//! STL byte stream against a stored reference. Any change to winding, triangle order or
+//! precision will show up here. If the output is meant to change, rerun the tests with
+//! `LITH_BLESS=1` to rewrite the references and commit the result.
+
+use std::path::PathBuf;
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator,
+    standard_image::StandardImagePreprocessor, ImagePreprocessor, LightMap, LithophaneGenerator,
+};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn light_map() -> LightMap {
+    let image = image::open(fixture("gradient.png")).expect("failed to open the fixture image");
+    StandardImagePreprocessor::default()
+        .width(12)
+        .transform(&image)
+}
+
+fn check_golden(name: &str, bytes: Vec<u8>) {
+    let path = fixture(name);
+    if std::env::var_os("LITH_BLESS").is_some() {
+        std::fs::write(&path, &bytes).expect("failed to write the reference file");
+        return;
+    }
+
+    let expected = std::fs::read(&path).expect("failed to read the reference file");
+    assert_eq!(bytes.len(), expected.len(), "STL size changed for {name}");
+    if let Some(i) = bytes.iter().zip(&expected).position(|(a, b)| a != b) {
+        panic!("STL output for {name} differs from the reference at byte {i}");
+    }
+}
+
+#[test]
+fn flat_mesh_matches_reference() {
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(light_map())
+        .unwrap();
+    check_golden("gradient_flat.stl", mesh.as_stl_bytes());
+}
+
+#[test]
+fn cylinder_mesh_matches_reference() {
+    let mesh = CylinderMeshGenerator::default()
+        .scaling(2.0)
+        .radius(20.0)
+        .height(20.0)
+        .generate(light_map())
+        .unwrap();
+    check_golden("gradient_cylinder.stl", mesh.as_stl_bytes());
+}
@@ -0,0 +1,25 @@
+//! Tests that compressed STL output holds exactly the uncompressed STL.
+
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+#[test]
+fn gzipped_stl_matches_uncompressed() {
+    let map = LightMap::from_fn(24, 16, |x, y| ((x * 7 + y * 3) % 11) as f32 / 10.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map)
+        .unwrap();
+
+    let compressed = mesh.as_stl_gz_bytes();
+    let mut decompressed = vec![];
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut decompressed)
+        .expect("the output should be valid gzip");
+
+    let stl = mesh.as_stl_bytes();
+    assert_eq!(decompressed, stl);
+    assert!(compressed.len() < stl.len());
+}
@@ -0,0 +1,29 @@
+//! Tests for the lightness histogram of a light map.
+
+use lith::gen::LightMap;
+
+#[test]
+fn uniform_map_fills_one_bin() {
+    let map = LightMap::from_fn(8, 5, |_, _| 0.42);
+    let histogram = map.histogram(10);
+    assert_eq!(histogram.len(), 10);
+    assert_eq!(histogram[4], 40);
+    assert_eq!(histogram.iter().sum::<u32>(), 40);
+}
+
+#[test]
+fn gradient_fills_every_bin_evenly() {
+    let map = LightMap::from_fn(100, 3, |x, _| x as f32 / 99.0);
+    let histogram = map.histogram(10);
+    assert_eq!(histogram.iter().sum::<u32>(), 300);
+    for &count in &histogram {
+        assert!((27..=33).contains(&count), "{histogram:?}");
+    }
+}
+
+#[test]
+fn out_of_range_values_go_to_the_end_bins() {
+    let map = LightMap::new(vec![-0.5, 0.0, 1.0, 1.5], (4, 1));
+    assert_eq!(map.histogram(4), [2, 0, 0, 2]);
+    assert!(map.histogram(0).is_empty());
+}
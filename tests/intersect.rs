@@ -0,0 +1,87 @@
+//! Tests for finding triangles that pass through each other.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::{Mesh, Vec3};
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+/// Get the two triangles of a unit square with corners `a`, `b`, `c` and `d` in order
+fn quad(a: Vec3, b: Vec3, c: Vec3, d: Vec3) -> [[Vec3; 3]; 2] {
+    [[a.clone(), b, c.clone()], [a, c, d]]
+}
+
+#[test]
+fn crossing_quads_are_found_on_grid_cell_boundaries() {
+    // Every triangle spans one unit, so the grid cells are one unit wide and start at the
+    // origin, where the first filler triangle pins the bounding box
+    let fillers = [
+        [
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 0.0, 0.0),
+            vec3(0.0, 1.0, 0.0),
+        ],
+        [
+            vec3(20.0, 20.0, 20.0),
+            vec3(19.0, 20.0, 20.0),
+            vec3(20.0, 19.0, 20.0),
+        ],
+    ];
+    // Shift the crossing so it lands on, just before and just after the cell boundaries
+    for s in [0.0, 0.5, 0.25, 1e-3, -1e-3, 0.999] {
+        let at = |x: f32, y: f32, z: f32| vec3(x + s, y + s, z + s);
+        let horizontal = quad(
+            at(4.5, 4.5, 5.0),
+            at(5.5, 4.5, 5.0),
+            at(5.5, 5.5, 5.0),
+            at(4.5, 5.5, 5.0),
+        );
+        let vertical = quad(
+            at(5.0, 4.2, 4.5),
+            at(5.0, 5.2, 4.5),
+            at(5.0, 5.2, 5.5),
+            at(5.0, 4.2, 5.5),
+        );
+        let triangles = fillers
+            .iter()
+            .cloned()
+            .chain(horizontal)
+            .chain(vertical)
+            .collect();
+        let found = Mesh::try_from_triangles(triangles)
+            .unwrap()
+            .find_self_intersections();
+        assert!(!found.is_empty(), "nothing found with a shift of {s}");
+        // Only a horizontal triangle (2, 3) can cross a vertical one (4, 5)
+        for (i, j) in found {
+            assert!((2..4).contains(&i) && (4..6).contains(&j), "({i}, {j})");
+        }
+    }
+}
+
+#[test]
+fn two_crossing_triangles_are_found() {
+    let flat = [
+        vec3(0.0, 0.0, 0.0),
+        vec3(4.0, 0.0, 0.0),
+        vec3(0.0, 4.0, 0.0),
+    ];
+    let upright = [
+        vec3(1.0, 1.0, -1.0),
+        vec3(1.0, 1.0, 1.0),
+        vec3(3.0, -1.0, 0.0),
+    ];
+    let mesh = Mesh::try_from_triangles(vec![flat, upright]).unwrap();
+    assert_eq!(mesh.find_self_intersections(), vec![(0, 1)]);
+}
+
+#[test]
+fn generated_flat_mesh_has_no_intersections() {
+    let map = LightMap::from_fn(12, 8, |x, y| ((x * 7 + y * 3) % 10) as f32 / 10.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map)
+        .unwrap();
+    assert!(mesh.find_self_intersections().is_empty());
+}
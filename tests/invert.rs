@@ -0,0 +1,71 @@
+//! Tests that inverting the relief raises the light areas and keeps the mesh closed.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator,
+};
+use lith::geo::{CheckStatus, Mesh};
+
+/// A light image, running from mid gray on the left to almost white on the right
+fn light() -> LightMap {
+    LightMap::from_fn(9, 5, |x, _| 0.5 + x as f32 / 20.0)
+}
+
+fn z_extents(mesh: &Mesh) -> (f32, f32) {
+    let (min, max) = mesh.bounds();
+    (min.z, max.z)
+}
+
+fn is_watertight(mesh: &Mesh) -> bool {
+    mesh.printability(0.0).check("Watertight").unwrap().status == CheckStatus::Pass
+}
+
+#[test]
+fn inverted_flat_relief_raises_light_areas() {
+    let generate = |invert| {
+        FlatMeshGenerator::default()
+            .scaling(2.0)
+            .invert(invert)
+            .generate(light())
+            .unwrap()
+    };
+    let (normal, inverted) = (generate(false), generate(true));
+
+    // The light image barely rises off the base normally, but reaches the top when inverted
+    let (bottom, top) = z_extents(&normal);
+    assert_eq!((bottom, top), (-2.0, -1.0));
+    let (bottom, top) = z_extents(&inverted);
+    assert_eq!(bottom, -2.0);
+    assert!((top + 0.2).abs() < 1e-6);
+    assert!(is_watertight(&normal));
+    assert!(is_watertight(&inverted));
+}
+
+#[test]
+fn inverted_cylinder_raises_light_areas() {
+    let generate = |invert| {
+        CylinderMeshGenerator::default()
+            .radius(10.0)
+            .height(5.0)
+            .scaling(2.0)
+            .invert(invert)
+            .generate(light())
+            .unwrap()
+    };
+    let outermost = |mesh: &Mesh| {
+        mesh.as_obj()
+            .lines()
+            .filter_map(|l| l.strip_prefix("v "))
+            .map(|v| {
+                let v: Vec<f32> = v.split(' ').map(|c| c.parse().unwrap()).collect();
+                v[0].hypot(v[1])
+            })
+            .fold(0.0, f32::max)
+    };
+    let (normal, inverted) = (generate(false), generate(true));
+
+    assert!((outermost(&normal) - 9.0).abs() < 1e-4);
+    assert!((outermost(&inverted) - 9.8).abs() < 1e-4);
+    assert!(is_watertight(&normal));
+    assert!(is_watertight(&inverted));
+}
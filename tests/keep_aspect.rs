@@ -0,0 +1,68 @@
+//! Tests that cylinders keeping the image aspect pad the light map instead of stretching it.
+
+use std::f32::consts::PI;
+
+use lith::gen::{cylinder_mesh::CylinderMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Get the distinct z values of the exterior vertices at `radius` from the z axis
+fn rows_at_radius(generator: CylinderMeshGenerator, map: LightMap, radius: f32) -> Vec<f32> {
+    let (vertices, _) = generator.generate(map).unwrap().to_indexed();
+    let mut rows: Vec<f32> = vertices
+        .iter()
+        .filter(|v| (v.x.hypot(v.y) - radius).abs() < 1e-3)
+        .map(|v| v.z)
+        .collect();
+    rows.sort_by(f32::total_cmp);
+    rows.dedup_by(|a, b| (*a - *b).abs() < 1e-4);
+    rows
+}
+
+#[test]
+fn wide_image_is_padded_above_and_below() {
+    // A 40 mm circumference and height, so a 20x5 image needs 20 rows for square pixels
+    let radius = 20.0 / PI;
+    let generator = || {
+        CylinderMeshGenerator::default()
+            .scaling(2.0)
+            .radius(radius)
+            .height(40.0)
+    };
+    let map = || LightMap::from_fn(20, 5, |_, _| 0.5);
+    let band = radius - 1.0;
+
+    // Stretched, the image covers every row of the wall
+    let stretched = rows_at_radius(generator(), map(), band);
+    assert_eq!(stretched.len(), 5);
+    assert_eq!(stretched[0], -32.0);
+    assert_eq!(stretched[4], 0.0);
+
+    // Padded, its five rows keep their 2 mm pitch in the middle of the wall...
+    let padded = rows_at_radius(generator().keep_aspect(true), map(), band);
+    assert_eq!(padded, vec![-22.0, -20.0, -18.0, -16.0, -14.0]);
+    // ...and the margin around them is left at full thickness
+    let margin = rows_at_radius(generator().keep_aspect(true), map(), radius);
+    assert_eq!(margin.len(), 15);
+    assert!(margin.iter().all(|z| !padded.contains(z)));
+}
+
+#[test]
+fn tall_image_is_padded_on_the_sides() {
+    let generator = CylinderMeshGenerator::default()
+        .scaling(2.0)
+        .radius(20.0 / PI)
+        .height(40.0)
+        .keep_aspect(true);
+    // 10 rows 4 mm apart need 10 columns around the 40 mm circumference
+    let map = LightMap::from_fn(4, 10, |_, _| 0.5);
+    let dims = (4, 10);
+    assert_eq!(generator.estimated_triangles(dims), 4 * 10 * 10);
+    let (_, triangles) = generator.generate(map).unwrap().to_indexed();
+    assert_eq!(triangles.len(), 4 * 10 * 10);
+}
+
+#[test]
+fn empty_map_pads_to_the_margin() {
+    let padded = LightMap::new(Vec::new(), (0, 0)).pad((1, 2, 3, 4), 0.25);
+    assert_eq!(padded.dims(), (4, 6));
+    assert!(padded.lightnesses().iter().all(|&l| l == 0.25));
+}
@@ -0,0 +1,49 @@
+//! Tests that light maps can be built and read from outside the crate.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+#[test]
+fn new_map_round_trips() {
+    let lightnesses = vec![0.0, 0.25, 0.5, 0.75, 1.0, 0.5];
+    let map = LightMap::new(lightnesses.clone(), (3, 2));
+    assert_eq!(map.dims(), (3, 2));
+    assert_eq!(map.lightnesses(), &lightnesses[..]);
+}
+
+#[test]
+fn new_map_matches_from_fn() {
+    let from_fn = LightMap::from_fn(3, 2, |x, y| (x + 3 * y) as f32 / 5.0);
+    let new = LightMap::new(from_fn.lightnesses().to_vec(), from_fn.dims());
+    let generator = || FlatMeshGenerator::default().scaling(2.0);
+    assert_eq!(
+        generator().generate(from_fn).unwrap().as_stl_bytes(),
+        generator().generate(new).unwrap().as_stl_bytes()
+    );
+}
+
+#[test]
+fn from_fn_builds_a_radial_gradient() {
+    // Dark in the middle, fading to white at the corners of a 9x7 map
+    let (cx, cy) = (4.0, 3.0);
+    let corner = f32::hypot(cx, cy);
+    let radial = |x: usize, y: usize| (x as f32 - cx).hypot(y as f32 - cy) / corner;
+    let map = LightMap::from_fn(9, 7, radial);
+
+    assert_eq!(map.dims(), (9, 7));
+    // Row by row from the top-left corner
+    for (i, &l) in map.lightnesses().iter().enumerate() {
+        assert_eq!(l, radial(i % 9, i / 9), "pixel {i}");
+    }
+    assert_eq!(map.lightnesses()[3 * 9 + 4], 0.0);
+    for i in [0, 8, 6 * 9, 6 * 9 + 8] {
+        assert!((map.lightnesses()[i] - 1.0).abs() < 1e-6);
+    }
+    // Symmetric about both axes through the center
+    for y in 0..7 {
+        for x in 0..9 {
+            let l = map.lightnesses()[y * 9 + x];
+            assert_eq!(l, map.lightnesses()[y * 9 + 8 - x]);
+            assert_eq!(l, map.lightnesses()[(6 - y) * 9 + x]);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Tests for the perceptual and linear paths from luminance to lightness.
+
+use lith::img::LightnessConversion;
+
+fn gray(v: u8) -> [u8; 3] {
+    [v, v, v]
+}
+
+#[test]
+fn linear_mode_differs_on_mid_gray() {
+    let perceptual = LightnessConversion::default().lightness(&gray(128));
+    let linear = LightnessConversion::default()
+        .perceptual(false)
+        .lightness(&gray(128));
+    // sRGB 128 is about 21.6% luminance but 53.6% perceived lightness
+    assert!((linear - 0.216).abs() < 0.005, "linear was {linear}");
+    assert!(
+        (perceptual - 0.536).abs() < 0.005,
+        "perceptual was {perceptual}"
+    );
+}
+
+#[test]
+fn linear_mode_is_monotonic() {
+    let conversion = LightnessConversion::default().perceptual(false);
+    let values: Vec<f32> = (0..=255).map(|v| conversion.lightness(&gray(v))).collect();
+    assert!(values.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(values[0], 0.0);
+    assert!((values[255] - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn invert_swaps_light_and_dark() {
+    let normal = LightnessConversion::default();
+    let inverted = LightnessConversion::default().invert(true);
+    for v in [0, 64, 128, 255] {
+        let pixel = [v, v, v, 255];
+        let (l, i) = (
+            normal.lightness_rgba(&pixel),
+            inverted.lightness_rgba(&pixel),
+        );
+        assert!((l + i - 1.0).abs() < 1e-6, "{v} gave {l} and {i}");
+    }
+}
+
+#[test]
+fn preprocessor_invert_reaches_the_light_map() {
+    use image::{DynamicImage, GrayImage};
+    use lith::gen::{standard_image::StandardImagePreprocessor, ImagePreprocessor};
+
+    let image =
+        DynamicImage::ImageLuma8(GrayImage::from_fn(4, 1, |x, _| image::Luma([x as u8 * 80])));
+    let normal = StandardImagePreprocessor::default()
+        .width(4)
+        .transform(&image);
+    let inverted = StandardImagePreprocessor::default()
+        .width(4)
+        .invert(true)
+        .transform(&image);
+    for (l, i) in normal.lightnesses().iter().zip(inverted.lightnesses()) {
+        assert!((l + i - 1.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn luminance_weights_change_pure_green() {
+    use lith::img::{srgb_to_luminance, srgb_to_luminance_weighted, LuminanceWeights};
+
+    let green = [0, 255, 0];
+    let rec709 = srgb_to_luminance_weighted(&green, LuminanceWeights::REC709);
+    let rec601 = srgb_to_luminance_weighted(&green, LuminanceWeights::REC601);
+    assert!((rec709 - 0.7152).abs() < 1e-6);
+    assert!((rec601 - 0.587).abs() < 1e-6);
+    assert_eq!(srgb_to_luminance(&green), rec709);
+
+    let lightness = |weights| {
+        LightnessConversion::default()
+            .weights(weights)
+            .lightness(&green)
+    };
+    assert!(lightness(LuminanceWeights::REC601) < lightness(LuminanceWeights::REC709));
+}
+
+#[test]
+fn luminance_presets_weigh_red_differently() {
+    use lith::img::{srgb_to_luminance_weighted, LuminanceWeights};
+
+    let red = |weights| srgb_to_luminance_weighted(&[255, 0, 0], weights);
+    let rec601 = red(LuminanceWeights::REC601);
+    let rec709 = red(LuminanceWeights::REC709);
+    let rec2020 = red(LuminanceWeights::REC2020);
+    assert!(rec709 < rec2020 && rec2020 < rec601);
+
+    // Every preset keeps white at full luminance
+    for weights in [
+        LuminanceWeights::REC601,
+        LuminanceWeights::REC709,
+        LuminanceWeights::REC2020,
+    ] {
+        let white = srgb_to_luminance_weighted(&[255; 3], weights);
+        assert!((white - 1.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn rec2020_preset_reaches_every_preprocessor() {
+    use image::{DynamicImage, RgbImage};
+    use lith::gen::{
+        blur_image::BlurImagePreprocessor, contrast_image::ContrastImagePreprocessor,
+        equalize_image::EqualizeImagePreprocessor, filter_image::FilterImagePreprocessor,
+        standard_image::StandardImagePreprocessor, tiled_image::TiledImagePreprocessor,
+        ImagePreprocessor, LightMap,
+    };
+    use lith::img::LuminanceWeights;
+
+    let red = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, image::Rgb([255, 0, 0])));
+    let weights = LuminanceWeights::REC2020;
+    let expected = LightnessConversion::default()
+        .weights(weights)
+        .lightness(&[255, 0, 0]);
+    let maps: [LightMap; 6] = [
+        StandardImagePreprocessor::default()
+            .weights(weights)
+            .transform(&red),
+        FilterImagePreprocessor::default()
+            .weights(weights)
+            .transform(&red),
+        BlurImagePreprocessor::default()
+            .weights(weights)
+            .transform(&red),
+        ContrastImagePreprocessor::default()
+            .weights(weights)
+            .transform(&red),
+        EqualizeImagePreprocessor::default()
+            .strength(0.0)
+            .weights(weights)
+            .transform(&red),
+        TiledImagePreprocessor::default()
+            .weights(weights)
+            .transform(&red),
+    ];
+    for map in maps {
+        assert!((map.lightnesses()[0] - expected).abs() < 1e-5);
+    }
+}
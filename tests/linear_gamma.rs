@@ -0,0 +1,48 @@
+//! Tests that a gamma in linear light differs from a gamma on the final lightness.
+
+use image::{DynamicImage, GrayImage};
+use lith::gen::{standard_image::StandardImagePreprocessor, ImagePreprocessor};
+use lith::img::{luminance_to_lightness, srgb_to_linear};
+
+fn mid_gray() -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, image::Luma([128])))
+}
+
+fn lightness(preprocessor: StandardImagePreprocessor) -> f32 {
+    preprocessor.transform(&mid_gray()).lightnesses()[0]
+}
+
+#[test]
+fn linear_gamma_differs_from_lightness_gamma_on_mid_gray() {
+    let y = srgb_to_linear(128.0 / 255.0);
+    let plain = lightness(StandardImagePreprocessor::default());
+    let linear = lightness(StandardImagePreprocessor::default().linear_gamma(2.0));
+    let final_gamma = lightness(StandardImagePreprocessor::default().gamma(2.0));
+
+    // The linear gamma squares the luminance before the lightness curve...
+    let expected_linear = luminance_to_lightness(y * y) / 100.0;
+    assert!(
+        (linear - expected_linear).abs() < 1e-4,
+        "linear gamma gave {linear}, not {expected_linear}"
+    );
+    // ...while the tone gamma squares the lightness after it
+    assert!(
+        (final_gamma - plain * plain).abs() < 1e-4,
+        "lightness gamma gave {final_gamma}, not {}",
+        plain * plain
+    );
+    // About 0.257 against 0.287 for sRGB 128
+    assert!(
+        (final_gamma - linear).abs() > 0.02,
+        "{linear} and {final_gamma} should differ"
+    );
+    assert!(linear < plain && final_gamma < plain);
+}
+
+#[test]
+fn unit_linear_gamma_changes_nothing() {
+    assert_eq!(
+        lightness(StandardImagePreprocessor::default().linear_gamma(1.0)),
+        lightness(StandardImagePreprocessor::default())
+    );
+}
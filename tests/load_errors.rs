@@ -0,0 +1,57 @@
+//! Tests that unreadable image files are reported with the right `LithError` variant.
+
+use std::path::PathBuf;
+
+use lith::{error::LithError, img::load_image};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// Write `bytes` to a uniquely named file in the temp directory and return its path
+fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("lith-{}-{name}", std::process::id()));
+    std::fs::write(&path, bytes).expect("failed to write the temp file");
+    path
+}
+
+#[test]
+fn fixture_loads() {
+    let image = load_image(fixture("gradient.png")).expect("the fixture should load");
+    assert_eq!((image.width(), image.height()), (12, 8));
+}
+
+#[test]
+fn truncated_png_is_corrupt() {
+    let bytes = std::fs::read(fixture("gradient.png")).unwrap();
+    let path = temp_file("truncated.png", &bytes[..bytes.len() / 2]);
+    let result = load_image(&path);
+    std::fs::remove_file(&path).ok();
+    assert!(
+        matches!(result, Err(LithError::CorruptImage(_))),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn text_file_is_unsupported() {
+    let path = temp_file(
+        "not-an-image.png",
+        b"this is just some text, not a picture\n",
+    );
+    let result = load_image(&path);
+    std::fs::remove_file(&path).ok();
+    assert!(
+        matches!(result, Err(LithError::UnsupportedFormat)),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn missing_file_is_io() {
+    let result = load_image(fixture("does-not-exist.png"));
+    assert!(matches!(result, Err(LithError::Io(_))), "{result:?}");
+}
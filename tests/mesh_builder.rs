@@ -0,0 +1,54 @@
+//! Tests for building meshes from raw triangles.
+
+use lith::geo::{Mesh, MeshError, Vec3};
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+/// A unit right tetrahedron with outward normals
+fn tetrahedron() -> Vec<[Vec3; 3]> {
+    let (o, x, y, z) = (
+        vec3(0.0, 0.0, 0.0),
+        vec3(1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+    );
+    vec![
+        [o.clone(), y.clone(), x.clone()],
+        [o.clone(), x.clone(), z.clone()],
+        [o, z.clone(), y.clone()],
+        [x, y, z],
+    ]
+}
+
+#[test]
+fn triangles_build_a_closed_mesh() {
+    let mesh = Mesh::try_from_triangles(tetrahedron()).unwrap();
+    assert_eq!(mesh.to_indexed().1.len(), 4);
+    assert!(mesh.is_watertight());
+    assert!((mesh.volume() - 1.0 / 6.0).abs() < 1e-6);
+}
+
+#[test]
+fn non_finite_vertices_are_rejected() {
+    for bad in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        let mut triangles = tetrahedron();
+        triangles[2][1].y = bad;
+        assert_eq!(
+            Mesh::try_from_triangles(triangles).err(),
+            Some(MeshError::NonFiniteVertex(2))
+        );
+    }
+}
+
+#[test]
+fn pushed_triangles_match_the_batch_constructor() {
+    let mut mesh = Mesh::try_from_triangles(vec![]).unwrap();
+    assert_eq!(mesh.to_indexed().1.len(), 0);
+    for triangle in tetrahedron() {
+        mesh.push_triangle(triangle);
+    }
+    let batch = Mesh::try_from_triangles(tetrahedron()).unwrap();
+    assert_eq!(mesh.as_stl_bytes(), batch.as_stl_bytes());
+}
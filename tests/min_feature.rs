@@ -0,0 +1,58 @@
+//! Tests that the minimum feature size removes specks while keeping larger features.
+
+mod common;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+const SIZE: usize = 15;
+
+/// A light map with a single dark pixel at (3, 3) and a dark 5x5 block from (8, 8)
+fn map() -> LightMap {
+    LightMap::from_fn(SIZE, SIZE, |x, y| {
+        let speck = (x, y) == (3, 3);
+        let block = (8..13).contains(&x) && (8..13).contains(&y);
+        if speck || block {
+            0.1
+        } else {
+            0.9
+        }
+    })
+}
+
+fn surface(generator: FlatMeshGenerator) -> Vec<f32> {
+    let mesh = generator.scaling(2.0).base(1.0).generate(map()).unwrap();
+    common::surface_heights(&mesh, (SIZE, SIZE), 1.0)
+}
+
+#[test]
+fn single_pixel_bump_is_removed_and_block_survives() {
+    let (dark, light) = (-0.2, -1.8);
+    let at = |heights: &[f32], x: usize, y: usize| heights[y * SIZE + x];
+
+    let unfiltered = surface(FlatMeshGenerator::default());
+    assert!((at(&unfiltered, 3, 3) - dark).abs() < 1e-5);
+
+    // A 3 mm minimum at 1 mm per pixel filters over a 3x3 window
+    let filtered = surface(FlatMeshGenerator::default().min_feature(3.0));
+    assert!(
+        (at(&filtered, 3, 3) - light).abs() < 1e-5,
+        "the speck stood at {}",
+        at(&filtered, 3, 3)
+    );
+    // The block is wider than the window and keeps its full height and shape
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            if (x, y) != (3, 3) {
+                assert_eq!(at(&filtered, x, y), at(&unfiltered, x, y), "({x}, {y})");
+            }
+        }
+    }
+}
+
+#[test]
+fn feature_size_below_two_pixels_changes_nothing() {
+    assert_eq!(
+        surface(FlatMeshGenerator::default().min_feature(1.0)),
+        surface(FlatMeshGenerator::default())
+    );
+}
@@ -0,0 +1,93 @@
+//! Tests that the minimum thickness keeps the surface off the back of the lithophane.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Get the z coordinate of every vertex in a binary STL
+fn vertex_heights(stl: &[u8]) -> Vec<f32> {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                let z = 12 + i * 12 + 8;
+                f32::from_le_bytes(t[z..z + 4].try_into().unwrap())
+            })
+        })
+        .collect()
+}
+
+/// A gradient from black on the left to pure white on the right
+fn gradient() -> LightMap {
+    LightMap::from_fn(9, 4, |x, _| x as f32 / 8.0)
+}
+
+#[test]
+fn surface_stays_above_min_thickness() {
+    for ramp in [0, 2] {
+        let stl = FlatMeshGenerator::default()
+            .scaling(3.0)
+            .min_thickness(0.8)
+            .edge_ramp(ramp)
+            .generate(gradient())
+            .unwrap()
+            .as_stl_bytes();
+        // Every vertex is either on the back at -3 or on the surface at least 0.8 above it
+        for z in vertex_heights(&stl) {
+            assert!(z == -3.0 || z >= -2.2 - 1e-6, "vertex at z = {z}");
+        }
+        if ramp == 0 {
+            // Black pixels still reach the top
+            assert!(vertex_heights(&stl).contains(&0.0));
+        }
+    }
+}
+
+#[test]
+fn zero_min_thickness_changes_nothing() {
+    let plain = FlatMeshGenerator::default()
+        .scaling(3.0)
+        .generate(gradient())
+        .unwrap();
+    let clamped = FlatMeshGenerator::default()
+        .scaling(3.0)
+        .min_thickness(0.0)
+        .generate(gradient())
+        .unwrap();
+    assert_eq!(plain.as_stl_bytes(), clamped.as_stl_bytes());
+}
+
+#[test]
+fn min_thickness_counts_the_base() {
+    let generate = |t: f32| {
+        FlatMeshGenerator::default()
+            .scaling(2.0)
+            .base_thickness(1.0)
+            .min_thickness(t)
+            .generate(gradient())
+            .unwrap()
+            .as_stl_bytes()
+    };
+    let plain = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base_thickness(1.0)
+        .generate(gradient())
+        .unwrap()
+        .as_stl_bytes();
+
+    // The base alone is already this thick, so nothing is raised
+    assert_eq!(generate(1.0), plain);
+
+    // Thicker than the base, the lightest pixels rise off it but black still reaches the top
+    let heights = vertex_heights(&generate(2.0));
+    for z in &heights {
+        assert!(*z == -3.0 || *z >= -1.0 - 1e-6, "vertex at z = {z}");
+    }
+    assert!(heights.contains(&0.0));
+    assert!(heights.iter().any(|&z| z > -1.0 + 1e-3 && z < 0.0));
+
+    // Only the full relief depth plus the base flattens the surface
+    let heights = vertex_heights(&generate(3.0));
+    assert!(
+        heights.iter().all(|&z| z == -3.0 || z == 0.0),
+        "{heights:?}"
+    );
+}
@@ -0,0 +1,43 @@
+//! Tests that the mirrored back of a two-sided lithophane reflects the front relief.
+
+use std::collections::HashSet;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// Get every vertex of a binary STL, quantized so equal positions compare equal
+fn vertices(stl: &[u8]) -> HashSet<[i64; 3]> {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                let coord = |c: usize| {
+                    let start = 12 + i * 12 + c * 4;
+                    let v = f32::from_le_bytes(t[start..start + 4].try_into().unwrap());
+                    (v * 1e4).round() as i64
+                };
+                [coord(0), coord(1), coord(2)]
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn back_is_mirror_of_front() {
+    let map = LightMap::from_fn(5, 4, |x, y| ((x * 3 + y * 5) % 7) as f32 / 6.0);
+    let stl = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .mirrored_back(true)
+        .generate(map)
+        .unwrap()
+        .as_stl_bytes();
+    let vertices = vertices(&stl);
+
+    // The base plane is at z = -2, and x runs from 0 to 4
+    let base = -2 * 10_000;
+    let front: Vec<_> = vertices.iter().filter(|v| v[2] > base).collect();
+    assert!(!front.is_empty());
+    for v in front {
+        let mirrored = [4 * 10_000 - v[0], v[1], 2 * base - v[2]];
+        assert!(vertices.contains(&mirrored), "no back vertex for {v:?}");
+    }
+}
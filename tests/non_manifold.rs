@@ -0,0 +1,68 @@
+//! Tests for finding the edges that leave a mesh open.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::{Mesh, Vec3};
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+/// Sort the endpoints of each edge and then the edges, so they can be compared as sets
+fn edge_set(edges: &[(Vec3, Vec3)]) -> Vec<[[f32; 3]; 2]> {
+    let mut set: Vec<_> = edges
+        .iter()
+        .map(|(a, b)| {
+            let mut pair = [[a.x, a.y, a.z], [b.x, b.y, b.z]];
+            pair.sort_by(|p, q| p.partial_cmp(q).unwrap());
+            pair
+        })
+        .collect();
+    set.sort_by(|p, q| p.partial_cmp(q).unwrap());
+    set
+}
+
+#[test]
+fn removed_triangle_leaves_exactly_its_edges() {
+    let map = LightMap::from_fn(7, 5, |x, y| ((x + 2 * y) % 4) as f32 / 5.0);
+    let mesh = FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .generate(map)
+        .unwrap();
+    assert!(mesh.non_manifold_edges().is_empty());
+
+    let (vertices, triangles) = mesh.to_indexed();
+    let removed = triangles.len() / 3;
+    let t = triangles[removed].map(|i| vertices[i].clone());
+    let broken = Mesh::new(
+        triangles
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != removed)
+            .flat_map(|(_, t)| t.map(|i| vertices[i].clone()))
+            .collect(),
+    );
+
+    let expected = [
+        (t[0].clone(), t[1].clone()),
+        (t[1].clone(), t[2].clone()),
+        (t[2].clone(), t[0].clone()),
+    ];
+    assert_eq!(edge_set(&broken.non_manifold_edges()), edge_set(&expected));
+}
+
+#[test]
+fn debug_mesh_draws_a_closed_prism_per_edge() {
+    let edges = [
+        (vec3(0.0, 0.0, 0.0), vec3(4.0, 0.0, 0.0)),
+        (vec3(0.0, 0.0, 0.0), vec3(0.0, 3.0, 1.0)),
+    ];
+    let debug = Mesh::edges_debug_mesh(&edges, 0.1);
+    // Two end caps and three rectangular sides for each edge
+    assert_eq!(debug.to_indexed().1.len(), 8 * edges.len());
+    let prism = Mesh::edges_debug_mesh(&edges[..1], 0.1);
+    assert!(prism.is_watertight());
+    assert!(prism.volume() > 0.0);
+    let (min, max) = prism.bounds();
+    assert!((min.x, max.x) == (0.0, 4.0));
+    assert!(max.y - min.y <= 0.2 + 1e-6 && max.z - min.z <= 0.2 + 1e-6);
+}
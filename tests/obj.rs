@@ -0,0 +1,64 @@
+//! Tests that OBJ export shares vertices without losing or merging triangles.
+
+use std::collections::HashSet;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+#[test]
+fn obj_has_every_face_and_unique_vertices() {
+    let map = LightMap::from_fn(16, 12, |x, y| ((x * 3 + y * 7) % 10) as f32 / 10.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map)
+        .unwrap();
+    let obj = String::from_utf8(mesh.as_obj_bytes()).unwrap();
+
+    let stl = mesh.as_stl_bytes();
+    let triangles = u32::from_le_bytes(stl[80..84].try_into().unwrap()) as usize;
+    let faces = obj.lines().filter(|l| l.starts_with("f ")).count();
+    assert_eq!(faces, triangles);
+
+    let vertices: Vec<[u32; 3]> = obj
+        .lines()
+        .filter_map(|l| l.strip_prefix("v "))
+        .map(|l| {
+            let coords: Vec<u32> = l
+                .split_whitespace()
+                .map(|c| c.parse::<f32>().unwrap().to_bits())
+                .collect();
+            coords.try_into().unwrap()
+        })
+        .collect();
+    let unique: HashSet<_> = vertices.iter().collect();
+    assert_eq!(unique.len(), vertices.len());
+    // An STL stores three vertices per triangle, but the grid corners are shared
+    assert!(vertices.len() < triangles);
+}
+
+#[test]
+fn welded_obj_keeps_winding() {
+    let map = LightMap::from_fn(6, 5, |x, y| ((x + y) % 4) as f32 / 4.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map)
+        .unwrap();
+    let obj = mesh.as_obj();
+
+    let vertices: Vec<Vec<f32>> = obj
+        .lines()
+        .filter_map(|l| l.strip_prefix("v "))
+        .map(|l| l.split_whitespace().map(|c| c.parse().unwrap()).collect())
+        .collect();
+    let faces = obj.lines().filter_map(|l| l.strip_prefix("f "));
+    let stl = mesh.as_stl_bytes();
+    for (face, triangle) in faces.zip(stl[84..].chunks_exact(50)) {
+        for (i, index) in face.split_whitespace().enumerate() {
+            let v = &vertices[index.parse::<usize>().unwrap() - 1];
+            for (c, coord) in v.iter().enumerate() {
+                let start = 12 + i * 12 + c * 4;
+                let expected = f32::from_le_bytes(triangle[start..start + 4].try_into().unwrap());
+                assert!((coord - expected).abs() < 1e-4);
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Tests for generating lithophanes of a given physical size.
+
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, Rgb, RgbImage};
+use lith::error::LithError;
+use lith::options::{output_path, GenerationOptions, Preset, SlicerPreset};
+
+/// A 40x30 image fading from black on the left to white on the right
+fn image() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(40, 30, |x, _| {
+        Rgb([(x * 255 / 39) as u8; 3])
+    }))
+}
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn output_has_the_requested_dimensions() {
+    let mesh = GenerationOptions::default()
+        .resolution(40)
+        .physical_width_mm(80.0)
+        .relief_depth_mm(2.5)
+        .generate(&image())
+        .unwrap();
+    let size = mesh.dimensions();
+    // The height follows the image's aspect ratio, and pure black is the full relief depth
+    assert!(
+        close(size.x, 80.0) && close(size.y, 60.0) && close(size.z, 2.5),
+        "{size:?}"
+    );
+
+    let mesh = GenerationOptions::default()
+        .resolution(40)
+        .physical_width_mm(80.0)
+        .physical_height_mm(60.5)
+        .generate(&image())
+        .unwrap();
+    let size = mesh.dimensions();
+    assert!(close(size.x, 80.0) && close(size.y, 60.5), "{size:?}");
+}
+
+#[test]
+fn mismatched_height_is_rejected() {
+    let result = GenerationOptions::default()
+        .physical_width_mm(80.0)
+        .physical_height_mm(90.0)
+        .generate(&image());
+    assert!(matches!(
+        result.err(),
+        Some(LithError::AspectMismatch { requested_mm, .. }) if requested_mm == 90.0
+    ));
+}
+
+#[test]
+fn slicer_presets_center_the_piece_on_the_bed() {
+    let options = || {
+        GenerationOptions::default()
+            .resolution(40)
+            .physical_width_mm(80.0)
+            .relief_depth_mm(2.5)
+    };
+    let unplaced = options().generate(&image()).unwrap();
+    for (preset, center) in [
+        (SlicerPreset::PrusaSlicer, (125.0, 105.0)),
+        (SlicerPreset::Cura, (0.0, 0.0)),
+        (SlicerPreset::Bambu, (128.0, 128.0)),
+    ] {
+        assert_eq!(preset.bed_center(), center);
+        let mesh = options().slicer_preset(preset).generate(&image()).unwrap();
+        let (min, max) = mesh.bounds();
+        // Resting on the bed with the footprint centered on it
+        assert!(close(min.z, 0.0), "{preset:?} bottom at {}", min.z);
+        assert!(close((min.x + max.x) / 2.0, center.0), "{preset:?}");
+        assert!(close((min.y + max.y) / 2.0, center.1), "{preset:?}");
+        // Only moved, not turned: same size, and the thick black side still faces +Z
+        let (a, b) = (mesh.dimensions(), unplaced.dimensions());
+        assert!(close(a.x, b.x) && close(a.y, b.y) && close(a.z, b.z));
+        let (vertices, _) = mesh.to_indexed();
+        let top_at = |x: f32| {
+            vertices
+                .iter()
+                .filter(|v| close(v.x, x))
+                .map(|v| v.z)
+                .fold(f32::MIN, f32::max)
+        };
+        assert!(close(top_at(min.x), max.z), "{preset:?}");
+        assert!(top_at(max.x) < max.z - 2.0, "{preset:?}");
+    }
+}
+
+#[test]
+fn preset_sets_its_dimensions_and_overrides_win() {
+    let preset = Preset::find("7X9 CM FRAME INSERT").unwrap();
+    assert_eq!(preset.name, "7x9 cm frame insert");
+    // A portrait image in the 7:9 aspect of the insert
+    let portrait = DynamicImage::ImageRgb8(RgbImage::from_fn(35, 45, |x, _| {
+        Rgb([(x * 255 / 34) as u8; 3])
+    }));
+
+    let size = preset.options().generate(&portrait).unwrap().dimensions();
+    assert!(
+        close(size.x, 70.0) && close(size.y, 90.0) && close(size.z, 3.0),
+        "{size:?}"
+    );
+
+    let size = preset
+        .options()
+        .physical_width_mm(35.0)
+        .physical_height_mm(45.0)
+        .relief_depth_mm(1.5)
+        .generate(&portrait)
+        .unwrap()
+        .dimensions();
+    assert!(
+        close(size.x, 35.0) && close(size.y, 45.0) && close(size.z, 1.5),
+        "{size:?}"
+    );
+
+    // The insert needs an exact height, so a landscape image is rejected
+    assert!(matches!(
+        preset.options().generate(&image()).err(),
+        Some(LithError::AspectMismatch { .. })
+    ));
+}
+
+#[test]
+fn output_path_needs_both_the_image_and_its_path() {
+    let image = image();
+    let source = Path::new("photos/cat.png");
+    assert_eq!(
+        output_path(Some(&image), Some(source), false),
+        Some(PathBuf::from("photos/cat.stl"))
+    );
+    assert_eq!(
+        output_path(Some(&image), Some(source), true),
+        Some(PathBuf::from("photos/cat.stl.gz"))
+    );
+    // A decoded image whose path was lost, or a path whose image failed to load
+    assert_eq!(output_path(Some(&image), None, false), None);
+    assert_eq!(output_path(None, Some(source), false), None);
+    assert_eq!(output_path(None, None, true), None);
+}
@@ -0,0 +1,48 @@
+//! Tests that light maps are rotated to the requested orientation.
+
+use lith::gen::{GenError, LightMap, Orientation};
+
+fn gradient(width: usize, height: usize) -> LightMap {
+    LightMap::from_fn(width, height, |x, y| (x + y * width) as f32)
+}
+
+#[test]
+fn landscape_and_portrait_match_the_target() {
+    let landscape = gradient(30, 20);
+    let portrait = gradient(20, 30);
+
+    assert_eq!(
+        landscape.orient(Orientation::Portrait).unwrap().dims(),
+        (20, 30)
+    );
+    assert_eq!(
+        portrait.orient(Orientation::Portrait).unwrap().dims(),
+        (20, 30)
+    );
+    assert_eq!(
+        landscape.orient(Orientation::Landscape).unwrap().dims(),
+        (30, 20)
+    );
+    assert_eq!(
+        portrait.orient(Orientation::Landscape).unwrap().dims(),
+        (30, 20)
+    );
+}
+
+#[test]
+fn mismatched_lightnesses_are_an_error() {
+    // Too few lightnesses for a 3x2 map, which would read out of bounds when rotated
+    let short = LightMap::new(vec![0.5; 4], (3, 2));
+    assert_eq!(
+        short.orient(Orientation::Portrait).err(),
+        Some(GenError::DimsMismatch {
+            expected: 6,
+            found: 4
+        })
+    );
+    let empty = LightMap::new(Vec::new(), (0, 0));
+    assert_eq!(
+        empty.orient(Orientation::Landscape).err(),
+        Some(GenError::EmptyLightMap)
+    );
+}
@@ -0,0 +1,81 @@
+//! Tests that PLY export writes a valid header and colors vertices by lightness.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap};
+
+/// A vertex position and its gray level
+type Vertex = ([f32; 3], u8);
+
+/// Split a binary PLY into its vertices and faces, checking the header as it goes
+fn parse_ply(ply: &[u8]) -> (Vec<Vertex>, Vec<[u32; 3]>) {
+    let end = b"end_header\n";
+    let body = ply.windows(end.len()).position(|w| w == end).unwrap() + end.len();
+    let header = std::str::from_utf8(&ply[..body]).unwrap();
+    let mut lines = header.lines();
+    assert_eq!(lines.next(), Some("ply"));
+    assert_eq!(lines.next(), Some("format binary_little_endian 1.0"));
+    let count = |line: Option<&str>, element: &str| -> usize {
+        line.and_then(|l| l.strip_prefix(element))
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+    let vertex_count = count(lines.next(), "element vertex ");
+    let properties: Vec<&str> = lines.by_ref().take(6).collect();
+    assert_eq!(
+        properties,
+        [
+            "property float x",
+            "property float y",
+            "property float z",
+            "property uchar red",
+            "property uchar green",
+            "property uchar blue"
+        ]
+    );
+    let face_count = count(lines.next(), "element face ");
+
+    let (vertex_bytes, face_bytes) = ply[body..].split_at(vertex_count * 15);
+    let vertices = vertex_bytes
+        .chunks_exact(15)
+        .map(|v| {
+            let position =
+                [0, 1, 2].map(|c| f32::from_le_bytes(v[c * 4..c * 4 + 4].try_into().unwrap()));
+            assert!(v[12] == v[13] && v[13] == v[14], "vertex isn't gray");
+            (position, v[12])
+        })
+        .collect();
+    assert_eq!(face_bytes.len(), face_count * 13);
+    let faces = face_bytes
+        .chunks_exact(13)
+        .map(|f| {
+            assert_eq!(f[0], 3);
+            [0, 1, 2].map(|i| u32::from_le_bytes(f[1 + i * 4..5 + i * 4].try_into().unwrap()))
+        })
+        .collect();
+    (vertices, faces)
+}
+
+#[test]
+fn colors_follow_lightness() {
+    // Black in the left column, white in the right and mid gray in between
+    let map = LightMap::from_fn(3, 2, |x, _| x as f32 / 2.0);
+    let ply = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .pixel_size(5.0)
+        .generate_ply(map)
+        .unwrap();
+    let (vertices, faces) = parse_ply(&ply);
+
+    for ([x, _, _], gray) in &vertices {
+        let expected = match *x as i32 {
+            0 => 0,
+            5 => 128,
+            10 => 255,
+            _ => panic!("unexpected vertex at x = {x}"),
+        };
+        assert_eq!(*gray, expected, "vertex at x = {x}");
+    }
+    for face in faces {
+        assert!(face.iter().all(|&i| (i as usize) < vertices.len()));
+    }
+}
@@ -0,0 +1,120 @@
+//! Tests for previews rendered at a higher resolution than the light map.
+
+use lith::gen::{preview, LightMap};
+
+/// A horizontal ramp from black on the left to white on the right
+fn ramp() -> LightMap {
+    LightMap::from_fn(5, 4, |x, _| x as f32 / 4.0)
+}
+
+#[test]
+fn supersampled_preview_doubles_dimensions() {
+    let map = ramp();
+    let heightmap = preview::heightmap(&map.upscale(2), 0.0, 1.0);
+    assert_eq!((heightmap.width(), heightmap.height()), (10, 8));
+    let hillshade = preview::hillshade_supersampled(&map, 2.0, 315.0, 45.0, 2);
+    assert_eq!((hillshade.width(), hillshade.height()), (10, 8));
+}
+
+#[test]
+fn upscaled_values_are_interpolated() {
+    let upscaled = ramp().upscale(2);
+    assert_eq!(upscaled.dims(), (10, 8));
+    let row: Vec<f32> = (0..10)
+        .map(|x| upscaled.sample_bilinear(x as f32, 3.0))
+        .collect();
+
+    // The new pixels sit a quarter of an old pixel either side of the old centers
+    let expected = [
+        0.0, 0.0625, 0.1875, 0.3125, 0.4375, 0.5625, 0.6875, 0.8125, 0.9375, 1.0,
+    ];
+    for (value, expected) in row.iter().zip(expected) {
+        assert!((value - expected).abs() < 1e-6, "{row:?}");
+    }
+    // No new pixel jumps by more than an old step would
+    assert!(row
+        .windows(2)
+        .all(|w| w[1] >= w[0] && w[1] - w[0] <= 0.125 + 1e-6));
+}
+
+#[test]
+fn empty_map_samples_as_zero() {
+    for dims in [(0, 0), (0, 3), (3, 0)] {
+        let empty = LightMap::new(Vec::new(), dims);
+        assert_eq!(empty.sample_bilinear(0.0, 0.0), 0.0);
+        assert_eq!(empty.sample_bilinear(2.5, -1.0), 0.0);
+    }
+    assert_eq!(LightMap::new(Vec::new(), (0, 0)).upscale(2).dims(), (0, 0));
+}
+
+#[test]
+fn supersampled_hillshade_keeps_the_shading() {
+    let map = ramp();
+    let coarse = preview::hillshade(&map, 2.0, 270.0, 45.0).to_luma8();
+    let fine = preview::hillshade_supersampled(&map, 2.0, 270.0, 45.0, 2).to_luma8();
+    // An even slope shades the same at any resolution away from the edges
+    let at = |image: &image::GrayImage, x, y| image.get_pixel(x, y).0[0] as i32;
+    assert!((at(&coarse, 2, 2) - at(&fine, 5, 4)).abs() <= 1);
+}
+
+#[test]
+fn ridge_is_lit_on_one_side_and_shadowed_on_the_other() {
+    // A dark ridge down the middle, which is the tallest part of the relief
+    let map = LightMap::from_fn(9, 5, |x, _| (x as f32 - 4.0).abs() / 4.0);
+    // Light from the left, low over the horizon
+    let shade = preview::hillshade(&map, 4.0, 270.0, 30.0).to_luma8();
+    let at = |x| shade.get_pixel(x, 2).0[0];
+    let flat = preview::hillshade(&LightMap::from_fn(9, 5, |_, _| 0.5), 4.0, 270.0, 30.0)
+        .to_luma8()
+        .get_pixel(4, 2)
+        .0[0];
+    for x in 1..4 {
+        assert!(at(x) > flat, "lit side {} vs flat {flat}", at(x));
+        assert!(
+            at(8 - x) < flat,
+            "shadowed side {} vs flat {flat}",
+            at(8 - x)
+        );
+    }
+}
+
+#[test]
+fn clipping_marks_pixels_outside_the_floor_and_ceiling() {
+    use preview::Clip;
+
+    let map = LightMap::new(vec![0.0, 0.1, 0.2, 0.5, 0.8, 0.9, 1.0], (7, 1));
+    assert_eq!(
+        preview::clipping(&map, 0.2, 0.8),
+        [
+            Clip::Floor,
+            Clip::Floor,
+            Clip::None,
+            Clip::None,
+            Clip::None,
+            Clip::Ceiling,
+            Clip::Ceiling,
+        ]
+    );
+    // The full range clamps nothing, even pure black and white
+    assert!(preview::clipping(&map, 0.0, 1.0)
+        .iter()
+        .all(|&c| c == Clip::None));
+}
+
+#[test]
+fn clipped_pixels_are_the_ones_levels_flattens() {
+    let map = LightMap::from_fn(11, 1, |x, _| x as f32 / 10.0);
+    let (floor, ceiling) = (0.25, 0.65);
+    let mut leveled = map.clone();
+    leveled.levels(floor, ceiling);
+    for (clip, &l) in preview::clipping(&map, floor, ceiling)
+        .iter()
+        .zip(leveled.lightnesses())
+    {
+        match clip {
+            preview::Clip::Floor => assert_eq!(l, 0.0),
+            preview::Clip::Ceiling => assert_eq!(l, 1.0),
+            preview::Clip::None => assert!(l > 0.0 && l < 1.0, "{l}"),
+        }
+    }
+}
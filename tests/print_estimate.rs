@@ -0,0 +1,22 @@
+//! Tests that the print time estimate scales with the amount of plastic.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+
+/// A solid black plate `width` mm wide, 50 mm tall and 2 mm thick
+fn plate(width: usize) -> lith::geo::Mesh {
+    FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(LightMap::from_fn(width + 1, 51, |_, _| 0.0))
+        .unwrap()
+}
+
+#[test]
+fn doubling_volume_doubles_time() {
+    let small = plate(100).print_estimate(0.2);
+    let large = plate(200).print_estimate(0.2);
+
+    assert!((large.volume_mm3 / small.volume_mm3 - 2.0).abs() < 1e-3);
+    assert_eq!(small.layers, large.layers);
+    let ratio = large.seconds / small.seconds;
+    assert!((1.8..2.2).contains(&ratio), "time grew by {ratio}x");
+}
@@ -0,0 +1,105 @@
+//! Tests that the printability checks catch the problems they are meant to.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::{CheckStatus, Mesh, Vec3};
+
+/// Read the triangles back out of a binary STL
+fn triangles(mesh: &Mesh) -> Vec<[Vec3; 3]> {
+    mesh.as_stl_bytes()[84..]
+        .chunks_exact(50)
+        .map(|t| {
+            [0, 1, 2].map(|i| {
+                let c = |a: usize| {
+                    let start = 12 + i * 12 + a * 4;
+                    f32::from_le_bytes(t[start..start + 4].try_into().unwrap())
+                };
+                Vec3 {
+                    x: c(0),
+                    y: c(1),
+                    z: c(2),
+                }
+            })
+        })
+        .collect()
+}
+
+/// A plate `thickness` mm thick with no relief
+fn plate(thickness: f32) -> Mesh {
+    FlatMeshGenerator::default()
+        .scaling(thickness)
+        .generate(LightMap::from_fn(6, 5, |_, _| 0.0))
+        .unwrap()
+}
+
+/// Get the status of the check called `name`
+fn status(mesh: &Mesh, name: &str) -> CheckStatus {
+    mesh.printability(0.8).check(name).unwrap().status
+}
+
+#[test]
+fn solid_plate_passes() {
+    let printability = plate(2.0).printability(0.8);
+    for check in &printability.checks {
+        assert_eq!(check.status, CheckStatus::Pass, "{check}");
+    }
+}
+
+#[test]
+fn missing_triangle_fails_watertight() {
+    let mut triangles = triangles(&plate(2.0));
+    triangles.remove(0);
+    let mesh = Mesh::try_from_triangles(triangles).unwrap();
+
+    assert_eq!(status(&mesh, "Watertight"), CheckStatus::Fail);
+    assert!(mesh.printability(0.8).check("Watertight").unwrap().count > 0);
+    assert_eq!(mesh.printability(0.8).status(), CheckStatus::Fail);
+}
+
+#[test]
+fn overlapping_plates_fail_self_intersection() {
+    let mut triangles = triangles(&plate(2.0));
+    let shifted: Vec<_> = triangles
+        .iter()
+        .map(|t| {
+            t.clone().map(|v| Vec3 {
+                x: v.x + 0.5,
+                y: v.y + 0.5,
+                z: v.z + 1.0,
+            })
+        })
+        .collect();
+    triangles.extend(shifted);
+    let mesh = Mesh::try_from_triangles(triangles).unwrap();
+
+    assert_eq!(status(&mesh, "Self-intersections"), CheckStatus::Fail);
+}
+
+#[test]
+fn thin_plate_warns() {
+    let mesh = plate(0.3);
+    assert_eq!(status(&mesh, "Thin walls"), CheckStatus::Warn);
+    assert_eq!(status(&mesh, "Watertight"), CheckStatus::Pass);
+    assert_eq!(mesh.printability(0.8).status(), CheckStatus::Warn);
+}
+
+#[test]
+fn raised_underside_warns_of_overhangs() {
+    // Lifting the plate off the bed turns its bottom into an unsupported overhang, but the
+    // copy still resting on the bed keeps the minimum height where it was
+    let mut triangles = triangles(&plate(2.0));
+    let lifted: Vec<_> = triangles
+        .iter()
+        .map(|t| {
+            t.clone().map(|v| Vec3 {
+                x: v.x + 20.0,
+                y: v.y,
+                z: v.z + 5.0,
+            })
+        })
+        .collect();
+    triangles.extend(lifted);
+    let mesh = Mesh::try_from_triangles(triangles).unwrap();
+
+    assert_eq!(status(&mesh, "Overhangs"), CheckStatus::Warn);
+    assert_eq!(status(&plate(2.0), "Overhangs"), CheckStatus::Pass);
+}
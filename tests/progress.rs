@@ -0,0 +1,79 @@
+//! Tests for the progress reported while generating.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, dot_mesh::DotMeshGenerator, flat_mesh::FlatMeshGenerator,
+    GenError, LightMap, LithophaneGenerator, TriangleOrder,
+};
+
+/// Generate with `generate`, collecting every fraction it reports
+fn fractions(
+    generate: impl FnOnce(&mut dyn FnMut(f32)) -> Result<lith::geo::Mesh, GenError>,
+) -> Vec<f32> {
+    let mut seen = vec![];
+    generate(&mut |fraction| seen.push(fraction)).ok().unwrap();
+    seen
+}
+
+#[test]
+fn progress_rises_to_one() {
+    let map = || LightMap::from_fn(20, 30, |x, y| ((x + y) % 5) as f32 / 5.0);
+    for seen in [
+        fractions(|p| FlatMeshGenerator::default().generate_with_progress(map(), p)),
+        fractions(|p| {
+            CylinderMeshGenerator::default()
+                .radius(20.0)
+                .height(10.0)
+                .generate_with_progress(map(), p)
+        }),
+    ] {
+        assert!(seen.len() > 2, "only {} progress calls", seen.len());
+        assert!(seen.windows(2).all(|w| w[0] <= w[1]));
+        assert!(seen.iter().all(|f| (0.0..=1.0).contains(f)));
+        assert_eq!(seen.last(), Some(&1.0));
+    }
+}
+
+#[test]
+fn failed_generation_reports_no_progress() {
+    let mut calls = 0;
+    let result = FlatMeshGenerator::default()
+        .generate_with_progress(LightMap::new(vec![], (0, 0)), &mut |_| calls += 1);
+    assert!(result.is_err());
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn every_order_and_generator_finishes_at_one() {
+    let map = || LightMap::from_fn(6, 5, |x, y| ((x * y) % 4) as f32 / 4.0);
+    for order in [
+        TriangleOrder::RowMajor,
+        TriangleOrder::Grouped,
+        TriangleOrder::Morton,
+    ] {
+        for seen in [
+            fractions(|p| {
+                FlatMeshGenerator::default()
+                    .order(order)
+                    .generate_with_progress(map(), p)
+            }),
+            fractions(|p| {
+                CylinderMeshGenerator::default()
+                    .radius(20.0)
+                    .height(10.0)
+                    .order(order)
+                    .generate_with_progress(map(), p)
+            }),
+        ] {
+            assert!(seen.windows(2).all(|w| w[0] <= w[1]), "{order:?}");
+            assert_eq!(seen.last(), Some(&1.0), "{order:?}");
+        }
+    }
+
+    let seen = fractions(|p| {
+        DotMeshGenerator::default()
+            .scaling(2.0)
+            .generate_with_progress(map(), p)
+    });
+    assert!(seen.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(seen.last(), Some(&1.0));
+}
@@ -0,0 +1,55 @@
+//! Tests for embedding QR codes into light maps.
+#![cfg(feature = "qr")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use lith::error::LithError;
+use lith::gen::{
+    flat_mesh::FlatMeshGenerator,
+    qr::{qr_light_map, Corner},
+    LightMap, LithophaneGenerator,
+};
+
+#[test]
+fn qr_region_has_two_relief_levels() {
+    // "lith" fits in a 21 module code, which is 29 pixels with the quiet zone
+    assert_eq!(qr_light_map("lith", 1).unwrap().dims(), (29, 29));
+
+    let (width, height) = (48, 36);
+    let mut map = LightMap::from_fn(width, height, |x, y| (x + y) as f32 / 100.0);
+    map.embed_qr("lith", Corner::BottomRight, 1).unwrap();
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base(0.5)
+        .generate(map)
+        .unwrap();
+    let heights = common::surface_heights(&mesh, (width, height), 1.0);
+
+    let (left, top) = (width - 29, height - 29);
+    let mut levels = BTreeSet::new();
+    for y in top..height {
+        for x in left..width {
+            levels.insert((heights[y * width + x] * 1e4).round() as i64);
+        }
+    }
+    // Dark modules stand at the top and light modules keep a thin layer
+    assert_eq!(levels, BTreeSet::from([-17000, 0]));
+    // The first module inside the quiet zone is the corner of a finder pattern
+    assert_eq!(heights[(top + 4) * width + left + 4], 0.0);
+    assert!((heights[top * width + left] + 1.7).abs() < 1e-5);
+    // Outside the code the gradient is left alone
+    let (x, y) = (left - 1, top - 1);
+    assert_eq!(heights[0], 0.0);
+    assert!((heights[y * width + x] + 2.0 * (x + y) as f32 / 100.0).abs() < 1e-5);
+}
+
+#[test]
+fn code_larger_than_the_map_is_rejected() {
+    let mut map = LightMap::from_fn(20, 40, |_, _| 0.5);
+    assert!(matches!(
+        map.embed_qr("lith", Corner::TopLeft, 1),
+        Err(LithError::InvalidOptions(_))
+    ));
+}
@@ -0,0 +1,51 @@
+//! Tests for decoding camera RAW files.
+//!
+//! `fixtures/bayer.dng` is a 16x12 uncompressed RGGB sensor with a white level of 4095 and a
+//! neutral white balance. Its color matrix describes a sensor whose first channel records
+//! green and whose second records red. The top left quarter has only the first channel lit,
+//! the bottom left only the second, and the right half is neutral: half exposure on top and
+//! a tenth below.
+#![cfg(feature = "raw")]
+
+use std::path::PathBuf;
+
+use lith::gen::{standard_image::StandardImagePreprocessor, ImagePreprocessor};
+
+fn fixture() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/bayer.dng")
+}
+
+#[test]
+fn dng_decodes_to_a_light_map() {
+    let image = lith::img::load_image(fixture()).unwrap();
+    // Each 2x2 block of the sensor becomes one pixel
+    assert_eq!((image.width(), image.height()), (8, 6));
+
+    let map = StandardImagePreprocessor::default()
+        .width(8)
+        .transform(&image);
+    assert_eq!(map.dims(), (8, 6));
+    assert!(map
+        .lightnesses()
+        .iter()
+        .all(|l| l.is_finite() && (0.0..=1.0).contains(l)));
+
+    // The neutral half is gray, lighter on top
+    let at = |x: usize, y: usize| map.lightnesses()[y * 8 + x];
+    assert!(at(6, 1) > at(6, 4));
+}
+
+#[test]
+fn color_matrix_maps_sensor_channels_to_real_colors() {
+    let image = lith::img::load_image(fixture()).unwrap().to_rgb16();
+    let [r, g, b] = image.get_pixel(1, 1).0;
+    // The first channel records green, so it comes out green rather than red
+    assert!(g > r && g > b, "{:?}", [r, g, b]);
+    let [r, g, b] = image.get_pixel(1, 4).0;
+    assert!(r > g && r > b, "{:?}", [r, g, b]);
+
+    // A neutral sensor reading stays neutral
+    let [r, g, b] = image.get_pixel(6, 1).0;
+    let spread = r.max(g).max(b) - r.min(g).min(b);
+    assert!(spread < 256, "{:?}", [r, g, b]);
+}
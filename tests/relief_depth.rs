@@ -0,0 +1,59 @@
+//! Tests that `scaling` gives the same relief depth in the flat and cylinder generators.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator,
+};
+
+/// Get every vertex in a binary STL
+fn vertices(stl: &[u8]) -> Vec<[f32; 3]> {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                [0, 1, 2].map(|c| {
+                    let start = 12 + i * 12 + c * 4;
+                    f32::from_le_bytes(t[start..start + 4].try_into().unwrap())
+                })
+            })
+        })
+        .collect()
+}
+
+/// Get the spread between the smallest and largest value of `f` over the vertices
+fn spread(stl: &[u8], f: impl Fn(&[f32; 3]) -> f32) -> f32 {
+    let (lo, hi) = vertices(stl)
+        .iter()
+        .map(f)
+        .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    hi - lo
+}
+
+#[test]
+fn flat_and_cylinder_share_relief_depth() {
+    for lightness in [0.0, 0.25, 0.5] {
+        let map = || LightMap::from_fn(16, 6, |_, _| lightness);
+        let flat = FlatMeshGenerator::default()
+            .scaling(3.0)
+            .generate(map())
+            .unwrap();
+        let cylinder = CylinderMeshGenerator::default()
+            .scaling(3.0)
+            .radius(15.0)
+            .height(10.0)
+            .generate(map())
+            .unwrap();
+
+        let flat_depth = spread(&flat.as_stl_bytes(), |v| v[2]);
+        let cylinder_depth = spread(&cylinder.as_stl_bytes(), |v| v[0].hypot(v[1]));
+        let expected = 3.0 * (1.0 - lightness);
+        assert!(
+            (flat_depth - expected).abs() < 1e-4,
+            "flat depth {flat_depth}"
+        );
+        assert!(
+            (cylinder_depth - expected).abs() < 1e-4,
+            "cylinder depth {cylinder_depth}"
+        );
+    }
+}
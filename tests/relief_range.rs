@@ -0,0 +1,81 @@
+//! Tests that `relief_range` agrees with the meshes the generators produce.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator,
+};
+
+/// Get the lowest and highest z of any vertex in a binary STL
+fn z_extent(stl: &[u8]) -> (f32, f32) {
+    stl[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                let z = 12 + i * 12 + 8;
+                f32::from_le_bytes(t[z..z + 4].try_into().unwrap())
+            })
+        })
+        .fold((f32::MAX, f32::MIN), |(lo, hi), z| (lo.min(z), hi.max(z)))
+}
+
+/// A map with one black pixel and white everywhere else
+fn black_dot() -> LightMap {
+    LightMap::from_fn(4, 4, |x, y| if (x, y) == (1, 2) { 0.0 } else { 1.0 })
+}
+
+#[test]
+fn flat_range_matches_mesh() {
+    let generator = FlatMeshGenerator::default().scaling(2.0);
+    let (base, surface) = generator.relief_range();
+    assert_eq!((base, surface), (-2.0, 0.0));
+    assert_eq!(
+        z_extent(&generator.generate(black_dot()).unwrap().as_stl_bytes()),
+        (base, surface)
+    );
+}
+
+#[test]
+fn default_flat_range_matches_mesh() {
+    let generator = FlatMeshGenerator::default();
+    let (base, surface) = generator.relief_range();
+    let (lo, hi) = z_extent(&generator.generate(black_dot()).unwrap().as_stl_bytes());
+    // Without a scaling call the relief is upside down, so the base is above the surface
+    assert_eq!((lo, hi), (surface, base));
+}
+
+#[test]
+fn inverted_range_puts_black_at_the_bottom_of_the_relief() {
+    let generator = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base_thickness(0.5)
+        .invert(true);
+    assert_eq!(generator.relief_range(), (-2.5, -2.0));
+    // An all black map is one flat slab at the reported surface
+    let black = LightMap::from_fn(4, 4, |_, _| 0.0);
+    assert_eq!(
+        z_extent(&generator.generate(black).unwrap().as_stl_bytes()),
+        (-2.5, -2.0)
+    );
+
+    let cylinder = || {
+        CylinderMeshGenerator::default()
+            .scaling(2.0)
+            .radius(10.0)
+            .height(10.0)
+    };
+    assert_eq!(cylinder().relief_range(), (8.0, 10.0));
+    let (inner, outer) = cylinder().base_thickness(0.5).invert(true).relief_range();
+    assert_eq!((inner, outer), (7.5, 8.0));
+    let black = LightMap::from_fn(12, 4, |_, _| 0.0);
+    let (vertices, _) = cylinder()
+        .base_thickness(0.5)
+        .invert(true)
+        .generate(black)
+        .unwrap()
+        .to_indexed();
+    let widest = vertices
+        .iter()
+        .map(|v| v.x.hypot(v.y))
+        .fold(f32::MIN, f32::max);
+    assert!((widest - outer).abs() < 1e-4, "black reached {widest}");
+}
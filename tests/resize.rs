@@ -0,0 +1,75 @@
+//! Tests that preprocessors fit images to a target width, height or both.
+
+use image::{DynamicImage, GrayImage};
+use lith::gen::{
+    blur_image::BlurImagePreprocessor, contrast_image::ContrastImagePreprocessor,
+    equalize_image::EqualizeImagePreprocessor, filter_image::FilterImagePreprocessor,
+    standard_image::StandardImagePreprocessor, ImagePreprocessor,
+};
+
+/// A 2:1 landscape image
+fn landscape() -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(200, 100, |x, y| {
+        image::Luma([((x + y) % 256) as u8])
+    }))
+}
+
+/// Check that both preprocessors produce a light map of `dims`, and predict it
+fn assert_dims(width: usize, height: usize, dims: (usize, usize)) {
+    let standard = StandardImagePreprocessor::default()
+        .width(width)
+        .height(height);
+    assert_eq!(standard.output_dims(200, 100), dims);
+    assert_eq!(standard.transform(&landscape()).dims(), dims);
+
+    let filter = FilterImagePreprocessor::default()
+        .width(width)
+        .height(height);
+    assert_eq!(filter.output_dims(200, 100), dims);
+    assert_eq!(filter.transform(&landscape()).dims(), dims);
+}
+
+#[test]
+fn width_only_keeps_the_aspect_ratio() {
+    assert_dims(100, 0, (100, 50));
+}
+
+#[test]
+fn height_only_keeps_the_aspect_ratio() {
+    assert_dims(0, 40, (80, 40));
+}
+
+#[test]
+fn both_fit_within_the_box() {
+    // The height is the tighter bound here, and the width there
+    assert_dims(150, 50, (100, 50));
+    assert_dims(60, 50, (60, 30));
+}
+
+#[test]
+fn neither_keeps_the_original_size() {
+    assert_dims(0, 0, (200, 100));
+}
+
+/// Check that the preprocessors with only a width produce a light map of `dims`, and predict it
+fn assert_width_dims(width: usize, dims: (usize, usize)) {
+    let blur = BlurImagePreprocessor::default().width(width);
+    assert_eq!(blur.output_dims(200, 100), dims);
+    assert_eq!(blur.transform(&landscape()).dims(), dims);
+
+    let contrast = ContrastImagePreprocessor::default().width(width);
+    assert_eq!(contrast.output_dims(200, 100), dims);
+    assert_eq!(contrast.transform(&landscape()).dims(), dims);
+
+    let equalize = EqualizeImagePreprocessor::default().width(width);
+    assert_eq!(equalize.output_dims(200, 100), dims);
+    assert_eq!(equalize.transform(&landscape()).dims(), dims);
+}
+
+#[test]
+fn width_only_preprocessors_match_their_output_dims() {
+    assert_width_dims(100, (100, 50));
+    // No width keeps the original size, and a wider one doesn't scale up
+    assert_width_dims(0, (200, 100));
+    assert_width_dims(400, (200, 100));
+}
@@ -0,0 +1,45 @@
+//! Tests that small cylinders stay closed where the exterior meets the interior.
+
+use lith::gen::{cylinder_mesh::CylinderMeshGenerator, LightMap, LithophaneGenerator};
+
+fn map() -> LightMap {
+    LightMap::from_fn(48, 6, |x, y| ((x * 5 + y * 3) % 9) as f32 / 10.0)
+}
+
+#[test]
+fn small_cylinders_are_watertight() {
+    // Dense columns on a tiny radius put neighboring vertices very close together
+    for radius in [0.5, 1.0, 2.5] {
+        let generator = || {
+            CylinderMeshGenerator::default()
+                .radius(radius)
+                .height(1.0)
+                .scaling(0.3)
+        };
+        for mesh in [
+            generator().generate(map()).unwrap(),
+            generator().arc(10.0, 200.0).generate(map()).unwrap(),
+        ] {
+            assert!(mesh.open_edges().is_empty(), "open at radius {radius}");
+            assert!(
+                mesh.non_manifold_edges().is_empty(),
+                "non-manifold at radius {radius}"
+            );
+            assert!(mesh.volume() > 0.0);
+        }
+    }
+}
+
+#[test]
+fn bridges_join_the_walls_exactly() {
+    let mesh = CylinderMeshGenerator::default()
+        .radius(0.5)
+        .height(1.0)
+        .scaling(0.3)
+        .generate(map())
+        .unwrap();
+    // Each ring of the rims has as many vertices as the walls have columns, so no vertex of
+    // a bridge sits apart from the walls it joins
+    let (vertices, _) = mesh.to_indexed();
+    assert_eq!(vertices.len(), 2 * 48 * 6);
+}
@@ -0,0 +1,79 @@
+//! Tests for splitting meshes along a plane.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::{Mesh, Vec3};
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+/// A unit cube from the origin, with outward normals
+fn cube() -> Mesh {
+    let corner = |i: usize| vec3((i & 1) as f32, (i >> 1 & 1) as f32, (i >> 2 & 1) as f32);
+    // Each face as four corners counterclockwise from outside
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+    let triangles = faces
+        .iter()
+        .flat_map(|&[a, b, c, d]| [[a, b, c], [a, c, d]])
+        .map(|t| t.map(corner))
+        .collect();
+    Mesh::try_from_triangles(triangles).unwrap()
+}
+
+/// Split `mesh` and check both halves are closed and share the volume between them
+fn check_split(mesh: &Mesh, point: Vec3, normal: Vec3) -> (Mesh, Mesh) {
+    let volume = mesh.volume();
+    let (front, back) = mesh.split_by_plane(&point, &normal);
+    assert!(front.is_watertight(), "{:?}", front.open_edges());
+    assert!(back.is_watertight(), "{:?}", back.open_edges());
+    assert!(front.volume() > 0.0 && back.volume() > 0.0);
+    assert!(
+        (front.volume() + back.volume() - volume).abs() < volume * 1e-4,
+        "{} + {} vs {volume}",
+        front.volume(),
+        back.volume()
+    );
+    (front, back)
+}
+
+#[test]
+fn cube_splits_into_two_closed_halves() {
+    let cube = cube();
+    assert!(cube.is_watertight());
+    assert!((cube.volume() - 1.0).abs() < 1e-6);
+
+    let (top, bottom) = check_split(&cube, vec3(0.0, 0.0, 0.5), vec3(0.0, 0.0, 1.0));
+    assert!((top.volume() - 0.5).abs() < 1e-5);
+    assert_eq!(top.bounds().0.z, 0.5);
+    assert_eq!(bottom.bounds().1.z, 0.5);
+}
+
+#[test]
+fn relief_splits_across_its_surface() {
+    let map = LightMap::from_fn(10, 8, |x, y| ((x * 3 + y) % 5) as f32 / 6.0);
+    let mesh = FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .base(0.6)
+        .generate(map)
+        .unwrap();
+    check_split(&mesh, vec3(4.3, 0.0, 0.0), vec3(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn cut_through_a_hole_caps_around_it() {
+    let map = LightMap::from_fn(12, 12, |x, y| ((x + y) % 3) as f32 / 4.0);
+    let mesh = FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .hang_hole(2.0, 4.0)
+        .generate(map)
+        .unwrap();
+    // Slice parallel to the face, so the cap is the outline with the hole in it
+    check_split(&mesh, vec3(0.0, 0.0, -1.5), vec3(0.0, 0.0, 1.0));
+}
@@ -0,0 +1,34 @@
+//! Tests that a stepped relief profile gives flat, evenly spaced levels.
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator, ReliefProfile};
+
+fn surface_levels(levels: usize) -> BTreeSet<i64> {
+    let map = LightMap::from_fn(10, 4, |x, _| x as f32 / 9.0);
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base(1.0)
+        .relief_profile(ReliefProfile::Stepped(levels))
+        .generate(map)
+        .unwrap();
+    common::surface_heights(&mesh, (10, 4), 1.0)
+        .into_iter()
+        .map(|z| (z * 1e4).round() as i64)
+        .collect()
+}
+
+#[test]
+fn two_steps_give_exactly_two_surface_heights() {
+    // The darker half stands at the top and the lighter half sinks the full depth
+    assert_eq!(surface_levels(2), BTreeSet::from([-20000, 0]));
+}
+
+#[test]
+fn each_step_is_evenly_spaced() {
+    assert_eq!(surface_levels(3), BTreeSet::from([-20000, -10000, 0]));
+    // Too few levels are treated as two
+    assert_eq!(surface_levels(1), surface_levels(2));
+}
@@ -0,0 +1,72 @@
+//! Tests for Catmull-Clark smoothing of the flat relief.
+
+mod common;
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::Mesh;
+
+/// A noisy relief, so there is plenty of curvature to smooth
+fn map() -> LightMap {
+    LightMap::from_fn(9, 7, |x, y| ((x * 7 + y * 13) % 5) as f32 / 6.0)
+}
+
+fn generate(levels: usize) -> Mesh {
+    FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .subdivide_smooth(levels)
+        .generate(map())
+        .unwrap()
+}
+
+/// Count the triangles facing up, which are the relief surface
+fn surface_faces(mesh: &Mesh) -> usize {
+    let (vertices, triangles) = mesh.to_indexed();
+    triangles
+        .iter()
+        .filter(|t| {
+            let [a, b, c] = t.map(|i| vertices[i].clone());
+            (&b - &a).cross(&(&c - &a)).z > 0.0
+        })
+        .count()
+}
+
+/// Get the variance of the curvature (second differences along x and y, per unit length
+/// squared) over the inside of a surface of `dims` samples `spacing` apart
+fn curvature_variance(heights: &[f32], dims: (usize, usize), spacing: f32) -> f32 {
+    let (w, h) = dims;
+    let at = |x: usize, y: usize| heights[y * w + x];
+    let curvatures: Vec<f32> = (1..h - 1)
+        .flat_map(|y| (1..w - 1).map(move |x| (x, y)))
+        .flat_map(|(x, y)| {
+            [
+                at(x - 1, y) - 2.0 * at(x, y) + at(x + 1, y),
+                at(x, y - 1) - 2.0 * at(x, y) + at(x, y + 1),
+            ]
+        })
+        .map(|d| d / (spacing * spacing))
+        .collect();
+    let mean = curvatures.iter().sum::<f32>() / curvatures.len() as f32;
+    curvatures.iter().map(|c| (c - mean).powi(2)).sum::<f32>() / curvatures.len() as f32
+}
+
+#[test]
+fn one_level_quadruples_the_surface() {
+    let (coarse, fine) = (generate(0), generate(1));
+    assert_eq!(surface_faces(&fine), 4 * surface_faces(&coarse));
+    assert!(fine.is_watertight());
+    // The footprint is unchanged
+    let ((cmin, cmax), (fmin, fmax)) = (coarse.bounds(), fine.bounds());
+    assert_eq!(
+        (cmin.x, cmin.y, cmax.x, cmax.y),
+        (fmin.x, fmin.y, fmax.x, fmax.y)
+    );
+}
+
+#[test]
+fn smoothing_reduces_curvature_variance() {
+    let coarse = common::surface_heights(&generate(0), (9, 7), 1.0);
+    let fine = common::surface_heights(&generate(1), (17, 13), 0.5);
+    let before = curvature_variance(&coarse, (9, 7), 1.0);
+    let after = curvature_variance(&fine, (17, 13), 0.5);
+    assert!(after < before, "{after} vs {before}");
+}
@@ -0,0 +1,112 @@
+//! Tests that the thickness settings give a lithophane of a predictable physical size.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator,
+};
+use lith::geo::CheckStatus;
+
+/// Black on the left half, white on the right
+fn half_black() -> LightMap {
+    LightMap::from_fn(8, 5, |x, _| if x < 4 { 0.0 } else { 1.0 })
+}
+
+#[test]
+fn thickness_is_base_plus_relief() {
+    // 8 x 5 samples 0.5 mm apart make a 3.5 x 2 mm plate
+    let generator = FlatMeshGenerator::default()
+        .pixel_size(0.5)
+        .max_thickness(2.5)
+        .base_thickness(0.75);
+    assert_eq!(generator.relief_range(), (-3.25, 0.0));
+
+    let mesh = generator.generate(half_black()).unwrap();
+    let size = mesh.dimensions();
+    assert_eq!((size.x, size.y, size.z), (3.5, 2.0, 3.25));
+
+    // White pixels keep the base thickness over the back
+    let obj = mesh.as_obj();
+    let white = obj.lines().filter_map(|l| {
+        let v: Vec<f32> = l
+            .strip_prefix("v ")?
+            .split(' ')
+            .map(|c| c.parse().unwrap())
+            .collect();
+        (v[0] >= 2.0).then_some(v[2])
+    });
+    for z in white {
+        assert!(z == -3.25 || z == -2.5, "white vertex at z = {z}");
+    }
+}
+
+#[test]
+fn max_thickness_without_base_matches_scaling() {
+    let scaled = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(half_black())
+        .unwrap();
+    let thick = FlatMeshGenerator::default()
+        .max_thickness(2.0)
+        .base_thickness(0.0)
+        .generate(half_black())
+        .unwrap();
+    assert_eq!(scaled.as_stl_bytes(), thick.as_stl_bytes());
+}
+
+#[test]
+fn base_thickness_is_independent_of_scaling() {
+    for scaling in [1.0, 4.0] {
+        let mesh = FlatMeshGenerator::default()
+            .scaling(scaling)
+            .base_thickness(0.8)
+            .generate(LightMap::from_fn(4, 4, |_, _| 0.0))
+            .unwrap();
+        // The darkest pixels are the relief plus the backing away from the back
+        let size = mesh.dimensions();
+        assert!((size.z - (scaling + 0.8)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn cylinder_base_thickness_moves_the_inside_wall() {
+    let generator = CylinderMeshGenerator::default()
+        .radius(10.0)
+        .height(5.0)
+        .scaling(2.0)
+        .base_thickness(0.8);
+    assert_eq!(generator.relief_range(), (7.2, 10.0));
+
+    // Every vertex of an all black cylinder is on the outside or the inside wall
+    let obj = generator
+        .generate(LightMap::from_fn(8, 4, |_, _| 0.0))
+        .unwrap()
+        .as_obj();
+    for line in obj.lines().filter(|l| l.starts_with("v ")) {
+        let v: Vec<f32> = line[2..].split(' ').map(|c| c.parse().unwrap()).collect();
+        let r = v[0].hypot(v[1]);
+        assert!(
+            (r - 10.0).abs() < 1e-4 || (r - 7.2).abs() < 1e-4,
+            "vertex {r} from the axis"
+        );
+    }
+}
+
+#[test]
+fn base_closes_the_brim_and_bottom() {
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .base(0.8)
+        .generate(half_black())
+        .unwrap();
+    assert_eq!(
+        mesh.as_stl_bytes(),
+        FlatMeshGenerator::default()
+            .scaling(2.0)
+            .base_thickness(0.8)
+            .generate(half_black())
+            .unwrap()
+            .as_stl_bytes()
+    );
+    let watertight = mesh.printability(0.0).check("Watertight").unwrap().status;
+    assert_eq!(watertight, CheckStatus::Pass);
+}
@@ -0,0 +1,61 @@
+//! Tests for the streaming preprocessor on a PNG large enough to downsample.
+//!
+//! `fixtures/gradient_disk.png` is a 384x256 RGB image fading from black to red left to right
+//! and adding green top to bottom, with a bright blue disk of radius 70 centered at (240, 110).
+
+use std::path::PathBuf;
+
+use lith::gen::{
+    standard_image::StandardImagePreprocessor, tiled_image::TiledImagePreprocessor,
+    ImagePreprocessor,
+};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[test]
+fn streamed_file_matches_the_decoded_image() {
+    let path = fixture("gradient_disk.png");
+    let streamed = TiledImagePreprocessor::default()
+        .width(96)
+        .transform_file(&path)
+        .unwrap();
+    let decoded = TiledImagePreprocessor::default()
+        .width(96)
+        .transform(&image::open(&path).unwrap());
+    assert_eq!(streamed.dims(), (96, 64));
+    assert_eq!(streamed.lightnesses(), decoded.lightnesses());
+}
+
+#[test]
+fn streamed_file_is_close_to_the_standard_preprocessor() {
+    let path = fixture("gradient_disk.png");
+    let streamed = TiledImagePreprocessor::default()
+        .width(96)
+        .transform_file(&path)
+        .unwrap();
+    let standard = StandardImagePreprocessor::default()
+        .width(96)
+        .transform(&image::open(&path).unwrap());
+    assert_eq!(streamed.dims(), standard.dims());
+
+    // The two resize with different filters, so they only differ much along the disk's edge
+    let differences: Vec<f32> = streamed
+        .lightnesses()
+        .iter()
+        .zip(standard.lightnesses())
+        .map(|(a, b)| (a - b).abs())
+        .collect();
+    let mean = differences.iter().sum::<f32>() / differences.len() as f32;
+    let close = differences.iter().filter(|&&d| d < 0.02).count();
+    assert!(mean < 0.01, "mean difference was {mean}");
+    assert!(
+        close as f32 > 0.9 * differences.len() as f32,
+        "only {close} of {} pixels were close",
+        differences.len()
+    );
+}
@@ -0,0 +1,64 @@
+//! Tests for repeating a light map around the cylinder.
+
+use lith::gen::{cylinder_mesh::CylinderMeshGenerator, LightMap, LithophaneGenerator};
+
+/// A ramp from dark on the left to light on the right, so each tile has a hard seam
+fn ramp() -> LightMap {
+    LightMap::from_fn(6, 3, |x, y| (x as f32 + y as f32 / 4.0) / 8.0)
+}
+
+#[test]
+fn tiles_repeat_the_map() {
+    let map = ramp();
+    let tiled = map.tile_horizontal(3);
+    assert_eq!(tiled.dims(), (18, 3));
+    for (row, tiled_row) in map
+        .lightnesses()
+        .chunks_exact(6)
+        .zip(tiled.lightnesses().chunks_exact(18))
+    {
+        for tile in tiled_row.chunks_exact(6) {
+            assert_eq!(tile, row);
+        }
+    }
+}
+
+#[test]
+fn cylinder_tiles_match_a_pre_tiled_map() {
+    let generator = || {
+        CylinderMeshGenerator::default()
+            .scaling(2.0)
+            .radius(20.0)
+            .height(10.0)
+    };
+    let tiled = generator().tiles(3).generate(ramp()).unwrap();
+    let manual = generator().generate(ramp().tile_horizontal(3)).unwrap();
+    assert_eq!(tiled.as_stl_bytes(), manual.as_stl_bytes());
+    assert!(tiled.is_watertight());
+}
+
+#[test]
+fn blended_seam_flows_into_the_next_tile() {
+    let mut map = ramp();
+    map.blend_seam(3);
+    let tiled = map.tile_horizontal(2);
+    for (row, original) in tiled
+        .lightnesses()
+        .chunks_exact(12)
+        .zip(ramp().lightnesses().chunks_exact(6))
+    {
+        // Blending three columns leaves a quarter of the original step across the seam
+        let seam = (row[6] - row[5]).abs();
+        let step = (original[0] - original[5]).abs();
+        assert!((seam - step / 4.0).abs() < 1e-6, "seam {seam} vs {step}");
+        // The tiles themselves start unchanged
+        assert_eq!(row[..3], original[..3]);
+    }
+}
+
+#[test]
+fn empty_maps_tile_and_blend_without_panicking() {
+    let mut map = LightMap::new(vec![], (0, 4));
+    map.blend_seam(2);
+    assert_eq!(map.tile_horizontal(3).dims(), (0, 4));
+}
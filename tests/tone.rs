@@ -0,0 +1,53 @@
+//! Tests the gamma, contrast and brightness tone adjustments of the standard preprocessor.
+
+use image::{DynamicImage, GrayImage};
+use lith::gen::{standard_image::StandardImagePreprocessor, ImagePreprocessor};
+
+fn ramp() -> DynamicImage {
+    DynamicImage::ImageLuma8(GrayImage::from_fn(16, 2, |x, _| {
+        image::Luma([x as u8 * 17])
+    }))
+}
+
+fn lightnesses(preprocessor: StandardImagePreprocessor) -> Vec<f32> {
+    preprocessor
+        .width(16)
+        .transform(&ramp())
+        .lightnesses()
+        .to_vec()
+}
+
+#[test]
+fn neutral_tone_changes_nothing() {
+    assert_eq!(
+        lightnesses(
+            StandardImagePreprocessor::default()
+                .gamma(1.0)
+                .contrast(1.0)
+                .brightness(0.0)
+        ),
+        lightnesses(StandardImagePreprocessor::default())
+    );
+}
+
+#[test]
+fn tone_runs_gamma_then_contrast_then_brightness() {
+    let original = lightnesses(StandardImagePreprocessor::default());
+    let adjusted = lightnesses(
+        StandardImagePreprocessor::default()
+            .gamma(2.0)
+            .contrast(1.5)
+            .brightness(0.1),
+    );
+    for (&o, &a) in original.iter().zip(&adjusted) {
+        let expected = (((o.powf(2.0) - 0.5) * 1.5 + 0.5) + 0.1).clamp(0.0, 1.0);
+        assert!(
+            (a - expected).abs() < 1e-6,
+            "{o} became {a}, not {expected}"
+        );
+        assert!((0.0..=1.0).contains(&a));
+    }
+    // Strong enough to clip at both ends
+    assert!(adjusted.contains(&0.0));
+    assert!(adjusted.contains(&1.0));
+}
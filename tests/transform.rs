@@ -0,0 +1,72 @@
+//! Tests that the affine transforms move vertices where expected.
+
+use lith::gen::{flat_mesh::FlatMeshGenerator, LightMap, LithophaneGenerator};
+use lith::geo::{Mesh, Vec3};
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+/// Read the vertices back out of a binary STL
+fn vertices(mesh: &Mesh) -> Vec<[f32; 3]> {
+    mesh.as_stl_bytes()[84..]
+        .chunks_exact(50)
+        .flat_map(|t| {
+            (0..3).map(move |i| {
+                [0, 1, 2].map(|c| {
+                    let start = 12 + i * 12 + c * 4;
+                    f32::from_le_bytes(t[start..start + 4].try_into().unwrap())
+                })
+            })
+        })
+        .collect()
+}
+
+fn plate() -> Mesh {
+    FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(LightMap::from_fn(5, 4, |x, y| ((x + y) % 3) as f32 / 4.0))
+        .unwrap()
+}
+
+#[test]
+fn quarter_turn_maps_x_to_y() {
+    let mut mesh = Mesh::new(vec![
+        vec3(1.0, 0.0, 3.0),
+        vec3(0.0, 0.0, 3.0),
+        vec3(0.0, 0.0, 4.0),
+    ]);
+    mesh.rotate_z(std::f32::consts::FRAC_PI_2);
+    let [x, y, z] = vertices(&mesh)[0];
+    assert!(x.abs() < 1e-6 && (y - 1.0).abs() < 1e-6, "got ({x}, {y})");
+    assert_eq!(z, 3.0);
+}
+
+#[test]
+fn translate_shifts_bounds() {
+    let mut mesh = plate();
+    let (min, max) = mesh.bounds();
+    mesh.translate(&vec3(10.0, -5.0, 2.0));
+    let (moved_min, moved_max) = mesh.bounds();
+    assert_eq!(
+        (moved_min.x, moved_min.y, moved_min.z),
+        (min.x + 10.0, min.y - 5.0, min.z + 2.0)
+    );
+    assert_eq!(
+        (moved_max.x, moved_max.y, moved_max.z),
+        (max.x + 10.0, max.y - 5.0, max.z + 2.0)
+    );
+}
+
+#[test]
+fn mirroring_keeps_normals_outward() {
+    let mut mesh = plate();
+    let volume = mesh.volume();
+    mesh.scale(vec3(-2.0, 1.0, 1.0));
+    assert!(
+        (mesh.volume() - 2.0 * volume).abs() < 1e-3,
+        "{} vs {volume}",
+        mesh.volume()
+    );
+    assert!(mesh.non_manifold_edges().is_empty());
+}
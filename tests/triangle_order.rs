@@ -0,0 +1,77 @@
+//! Tests that the triangle order only changes where triangles appear in the output.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator, TriangleOrder,
+};
+use lith::geo::Mesh;
+
+const ORDERS: [TriangleOrder; 3] = [
+    TriangleOrder::RowMajor,
+    TriangleOrder::Grouped,
+    TriangleOrder::Morton,
+];
+
+fn map() -> LightMap {
+    LightMap::from_fn(9, 7, |x, y| ((x * 3 + y * 5) % 7) as f32 / 8.0)
+}
+
+/// Get the facets of the mesh's binary STL, sorted so the order they were emitted in is lost
+fn sorted_facets(mesh: Mesh) -> Vec<Vec<u8>> {
+    let mut facets: Vec<_> = mesh.as_stl_bytes()[84..]
+        .chunks_exact(50)
+        .map(<[u8]>::to_vec)
+        .collect();
+    facets.sort();
+    facets
+}
+
+#[test]
+fn flat_orders_give_the_same_triangles() {
+    let generate = |order| {
+        sorted_facets(
+            FlatMeshGenerator::default()
+                .scaling(2.0)
+                .order(order)
+                .generate(map())
+                .unwrap(),
+        )
+    };
+    let reference = generate(TriangleOrder::RowMajor);
+    for order in ORDERS {
+        assert!(generate(order) == reference, "{order:?} differs");
+    }
+}
+
+#[test]
+fn cylinder_orders_give_the_same_triangles() {
+    let generate = |order| {
+        sorted_facets(
+            CylinderMeshGenerator::default()
+                .scaling(2.0)
+                .radius(15.0)
+                .height(10.0)
+                .order(order)
+                .generate(map())
+                .unwrap(),
+        )
+    };
+    let reference = generate(TriangleOrder::RowMajor);
+    for order in ORDERS {
+        assert!(generate(order) == reference, "{order:?} differs");
+    }
+}
+
+#[test]
+fn orders_differ_in_sequence() {
+    let stl = |order| {
+        FlatMeshGenerator::default()
+            .scaling(2.0)
+            .order(order)
+            .generate(map())
+            .unwrap()
+            .as_stl_bytes()
+    };
+    assert!(stl(TriangleOrder::RowMajor) != stl(TriangleOrder::Grouped));
+    assert!(stl(TriangleOrder::Grouped) != stl(TriangleOrder::Morton));
+}
@@ -0,0 +1,47 @@
+//! Tests for the vector math on `Vec3`.
+
+use lith::geo::Vec3;
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+#[test]
+fn add_and_sub_are_component_wise() {
+    let (a, b) = (vec3(1.0, 2.0, 3.0), vec3(4.0, -5.0, 0.5));
+    assert_eq!(&a + &b, vec3(5.0, -3.0, 3.5));
+    assert_eq!(&a - &b, vec3(-3.0, 7.0, 2.5));
+}
+
+#[test]
+fn mul_scales_every_component() {
+    assert_eq!(&vec3(1.0, -2.0, 0.5) * 4.0, vec3(4.0, -8.0, 2.0));
+}
+
+#[test]
+fn dot_of_perpendicular_vectors_is_zero() {
+    assert_eq!(vec3(1.0, 2.0, 0.0).dot(&vec3(-2.0, 1.0, 7.0)), 0.0);
+    assert_eq!(vec3(1.0, 2.0, 3.0).dot(&vec3(4.0, 5.0, 6.0)), 32.0);
+}
+
+#[test]
+fn cross_follows_right_hand_rule() {
+    let (x, y, z) = (
+        vec3(1.0, 0.0, 0.0),
+        vec3(0.0, 1.0, 0.0),
+        vec3(0.0, 0.0, 1.0),
+    );
+    assert_eq!(x.cross(&y), z);
+    assert_eq!(y.cross(&z), x);
+    assert_eq!(y.cross(&x), vec3(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn length_and_normalized() {
+    let v = vec3(3.0, 0.0, 4.0);
+    assert_eq!(v.length(), 5.0);
+    let n = v.normalized();
+    assert!((n.length() - 1.0).abs() < 1e-6);
+    assert!((n.x - 0.6).abs() < 1e-6 && (n.z - 0.8).abs() < 1e-6);
+    assert_eq!(vec3(0.0, 0.0, 0.0).normalized(), vec3(0.0, 0.0, 0.0));
+}
@@ -0,0 +1,70 @@
+//! Tests for finding holes in a mesh.
+
+use lith::gen::{
+    cylinder_mesh::CylinderMeshGenerator, flat_mesh::FlatMeshGenerator, LightMap,
+    LithophaneGenerator,
+};
+use lith::geo::{Mesh, Vec3};
+
+fn map() -> LightMap {
+    LightMap::from_fn(12, 9, |x, y| ((x * 3 + y) % 7) as f32 / 8.0)
+}
+
+fn vec3(x: f32, y: f32, z: f32) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+#[test]
+fn generated_meshes_are_watertight() {
+    let flat = FlatMeshGenerator::default()
+        .max_thickness(3.0)
+        .base(0.8)
+        .generate(map())
+        .unwrap();
+    assert!(flat.is_watertight());
+    assert!(flat.open_edges().is_empty());
+
+    let cylinder = CylinderMeshGenerator::default()
+        .radius(20.0)
+        .height(15.0)
+        .scaling(2.0)
+        .generate(map())
+        .unwrap();
+    assert!(cylinder.is_watertight());
+    assert!(cylinder.open_edges().is_empty());
+}
+
+#[test]
+fn missing_triangle_leaves_open_edges() {
+    let mesh = FlatMeshGenerator::default()
+        .scaling(2.0)
+        .generate(map())
+        .unwrap();
+    // Drop the first triangle, leaving its three edges each used once
+    let (vertices, triangles) = mesh.to_indexed();
+    let soup = triangles[1..]
+        .iter()
+        .flat_map(|t| t.map(|i| vertices[i].clone()))
+        .collect();
+    let holed = Mesh::new(soup);
+    assert!(!holed.is_watertight());
+    assert_eq!(holed.open_edges().len(), 3);
+}
+
+#[test]
+fn shared_edges_are_not_open() {
+    // Three triangles fanned around one edge: it isn't open, but the mesh isn't watertight
+    let (a, b) = (vec3(0.0, 0.0, 0.0), vec3(1.0, 0.0, 0.0));
+    let fan = [
+        vec3(0.5, 1.0, 0.0),
+        vec3(0.5, -1.0, 0.0),
+        vec3(0.5, 0.0, 1.0),
+    ]
+    .into_iter()
+    .flat_map(|c| [a.clone(), b.clone(), c])
+    .collect();
+    let mesh = Mesh::new(fan);
+    assert!(!mesh.is_watertight());
+    assert_eq!(mesh.open_edges().len(), 6);
+    assert_eq!(mesh.non_manifold_edges().len(), 7);
+}